@@ -1,74 +1,186 @@
-use crate::game;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crate::{
+    card::{Card, Role, CARD_HEIGHT},
+    game, record,
+    rules::RuleSet,
+    scoring::Scoring,
+    strategy::{Lookahead, Strategy},
+};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
 use ratatui::{
     buffer::Buffer,
-    layout::{Constraint, Direction, Flex, Layout, Rect},
+    layout::{Constraint, Direction, Flex, Layout, Position, Rect},
     style::{Color, Style, Stylize},
     symbols::border,
     text::{Line, Span},
-    widgets::{Block, StatefulWidget, Widget},
+    widgets::{Block, Clear, Padding, Paragraph, StatefulWidget, Widget, Wrap},
     DefaultTerminal, Frame,
 };
-use std::io;
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+use strum::IntoEnumIterator;
 use tui_big_text::{BigText, PixelSize};
 
+mod keymap;
+mod menu;
+mod results;
+mod save;
+mod settings;
+mod undo;
+
+use keymap::{key_name, Command, Keymap};
+use menu::{Field, Item, Menu};
+use settings::Settings;
+pub use undo::UndoMode;
+
+const BOT_DELAY: Duration = Duration::from_millis(600);
+
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Slot(usize),
+    Deck,
+    Weapon,
+}
+
+enum Screen {
+    Menu(Menu),
+    Seed(String, Option<String>),
+    Statistics(results::Statistics),
+    Settings(Field),
+    Game(Box<State>),
+}
+
 pub struct State {
     turns: Vec<game::State>,
     use_weapon: bool,
+    message: Option<String>,
+    hint: Option<game::Action>,
+    bot: Option<(Box<dyn Strategy>, Instant)>,
+    undo: UndoMode,
+    undos: usize,
+    scoring: Scoring,
+    started: Instant,
+    elapsed: Duration,
+    // The clickable areas of the last frame.
+    targets: Vec<(Rect, Target)>,
+    help: bool,
+}
+
+impl State {
+    fn score(&self) -> isize {
+        self.scoring.score(&self.turns, self.elapsed)
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct App {
     exit: bool,
+    // The rules given on the command line. They take the place of the saved
+    // rules for new games, without being saved, until the player picks other
+    // rules in the settings.
+    rules: Option<RuleSet>,
+    seed: Option<u64>,
+    undo: UndoMode,
+    scoring: Scoring,
+    keymap: Keymap,
+    settings: Settings,
 }
 
 impl StatefulWidget for &mut App {
     type State = State;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        state.targets.clear();
         let current_state = state.turns.last().unwrap();
+        let can_run = current_state.legal_actions().contains(&game::Action::Run);
         let title = Line::from(" Scoundrel ".bold());
 
-        let status = Line::from(vec![
-            " Health ".into(),
-            current_state.health.to_string().green().bold(),
-            " | Used heal ".into(),
-            current_state.used_heal.to_string().bold(),
-            " | Deck ".into(),
-            current_state.deck.len().to_string().bold(),
-            " | Using weapon ".into(),
-            Into::<Span>::into(state.use_weapon.to_string()).style(
-                Style::default()
-                    .fg(if state.use_weapon {
-                        Color::Green
-                    } else {
-                        Color::Red
-                    })
-                    .bold(),
-            ),
-            " | Can run ".into(),
-            Into::<Span>::into(current_state.can_run.to_string()).style(
-                Style::default()
-                    .fg(if current_state.can_run {
-                        Color::Green
-                    } else {
-                        Color::Red
-                    })
+        let runs_left = match current_state.runs_left() {
+            None => Vec::new(),
+            Some(runs_left) => vec![" | Runs left ".into(), runs_left.to_string().bold()],
+        };
+        let undo = match state.undo {
+            UndoMode::Casual => Vec::new(),
+            UndoMode::Budget(_) => vec![
+                " | Undos left ".into(),
+                state
+                    .undo
+                    .undos_left(state.undos)
+                    .unwrap()
+                    .to_string()
                     .bold(),
-            ),
-            " ".into(),
-        ])
+            ],
+            UndoMode::Ironman => vec![" | ".into(), "Ironman".red().bold()],
+        };
+        let depth = if current_state.rules.endless {
+            vec![
+                " | Depth ".into(),
+                current_state.rooms_cleared.to_string().bold(),
+                " | Level ".into(),
+                (current_state.level + 1).to_string().bold(),
+            ]
+        } else {
+            Vec::new()
+        };
+        let status = Line::from(
+            [
+                vec![
+                    " Health ".into(),
+                    current_state.health.to_string().green().bold(),
+                    " | Score ".into(),
+                    state.score().to_string().bold(),
+                    " | Heals left ".into(),
+                    current_state.heals_left.to_string().bold(),
+                    " | Deck ".into(),
+                    current_state.deck.len().to_string().bold(),
+                    " | Using weapon ".into(),
+                    Into::<Span>::into(state.use_weapon.to_string()).style(
+                        Style::default()
+                            .fg(if state.use_weapon {
+                                Color::Green
+                            } else {
+                                Color::Red
+                            })
+                            .bold(),
+                    ),
+                    " | Can run ".into(),
+                    Into::<Span>::into(can_run.to_string()).style(
+                        Style::default()
+                            .fg(if can_run { Color::Green } else { Color::Red })
+                            .bold(),
+                    ),
+                ],
+                runs_left,
+                depth,
+                undo,
+                vec![
+                    " | Seed ".into(),
+                    current_state.seed.to_string().bold(),
+                    " | Rules ".into(),
+                    current_state.rules.to_string().bold(),
+                    " ".into(),
+                ],
+            ]
+            .concat(),
+        )
         .left_aligned();
-        let instructions = Line::from(vec![
-            " Toggle Use Weapon ".into(),
-            "<W>".blue().bold(),
-            " | Run ".into(),
-            "<R>".blue().bold(),
-            " | Undo ".into(),
-            "<U>".blue().bold(),
-            " | Quit ".into(),
-            "<Q> ".blue().bold(),
-        ])
+        let instructions = Line::from(
+            self.instructions(current_state.rules.room_size)
+                .into_iter()
+                .enumerate()
+                .flat_map(|(i, (label, keys))| {
+                    [
+                        format!("{}{label} ", if i == 0 { " " } else { " | " }).into(),
+                        format!("<{keys}>").blue().bold(),
+                    ]
+                })
+                .chain([" ".into()])
+                .collect::<Vec<Span>>(),
+        )
         .right_aligned();
 
         let block = Block::bordered()
@@ -77,8 +189,38 @@ impl StatefulWidget for &mut App {
             .title_bottom(instructions)
             .border_set(border::THICK);
 
+        let message_area = block.inner(area);
         block.render(area, buf);
 
+        let hinted_action = state.hint;
+        let message = match (&state.message, hinted_action) {
+            (Some(message), _) => Some(message.clone()),
+            (None, Some(game::Action::Run)) => Some("Hint: run from this room".to_string()),
+            (None, Some(game::Action::Play { slot, use_weapon })) => {
+                let card = current_state.open[slot].unwrap();
+                Some(if card.role == Role::Monster {
+                    format!(
+                        "Hint: fight card {} {}",
+                        slot + 1,
+                        if use_weapon {
+                            "with your weapon"
+                        } else {
+                            "barehanded"
+                        }
+                    )
+                } else {
+                    format!("Hint: play card {}", slot + 1)
+                })
+            }
+            (None, None) if state.bot.is_some() => Some("The bot is playing".to_string()),
+            (None, None) => None,
+        };
+        if let Some(message) = message {
+            Line::from(message.yellow().bold())
+                .centered()
+                .render(message_area.rows().next_back().unwrap_or_default(), buf);
+        }
+
         if current_state.game_over {
             let text_area = Layout::default()
                 .direction(Direction::Vertical)
@@ -89,27 +231,39 @@ impl StatefulWidget for &mut App {
             BigText::builder()
                 .pixel_size(PixelSize::Full)
                 .centered()
-                .lines(vec![
-                    "You".into(),
-                    if current_state.health <= 0 {
-                        "Lose".red().into()
-                    } else {
-                        "Win".green().into()
-                    },
-                ])
+                .lines(if current_state.rules.endless {
+                    vec![
+                        "Depth".into(),
+                        current_state.rooms_cleared.to_string().yellow().into(),
+                    ]
+                } else {
+                    vec![
+                        "You".into(),
+                        if current_state.health <= 0 {
+                            "Lose".red().into()
+                        } else {
+                            "Win".green().into()
+                        },
+                    ]
+                })
                 .build()
                 .render(text_area[0], buf);
 
+            let breakdown = match state.scoring {
+                Scoring::Official => current_state.score_breakdown().to_string(),
+                scoring => format!("{scoring} scoring"),
+            };
             Line::from_iter(vec![
                 "Score: ".into(),
                 Span::styled(
-                    current_state.score().to_string(),
+                    state.score().to_string(),
                     Style::default().fg(if current_state.health <= 0 {
                         Color::Red
                     } else {
                         Color::Green
                     }),
                 ),
+                format!(" ({breakdown})").into(),
             ])
             .centered()
             .render(text_area[1], buf);
@@ -124,13 +278,17 @@ impl StatefulWidget for &mut App {
             .constraints([Constraint::Ratio(1, 2); 2])
             .split(area);
 
+        // One column for the deck and one per room slot, with the margins
+        // shrinking as the room grows.
+        let columns = current_state.rules.room_size as u32 + 1;
+        let margin = 50u16.saturating_sub(6 * columns as u16);
         let room_area = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
                 [
-                    [Constraint::Percentage(20)].to_vec(),
-                    [Constraint::Ratio(1, 5); 5].to_vec(),
-                    [Constraint::Percentage(20)].to_vec(),
+                    [Constraint::Percentage(margin)].to_vec(),
+                    [Constraint::Ratio(1, columns)].repeat(columns as usize),
+                    [Constraint::Percentage(margin)].to_vec(),
                 ]
                 .concat(),
             )
@@ -152,6 +310,11 @@ impl StatefulWidget for &mut App {
                     .split(room_area[1])[1],
             );
 
+        let weapon_rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Fill(1)])
+            .split(inner_area[1]);
+
         let weapon_area = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
@@ -163,13 +326,16 @@ impl StatefulWidget for &mut App {
                 ]
                 .concat(),
             )
-            .split(inner_area[1]);
+            .split(weapon_rows[1]);
 
+        state.targets.push((room_area[1], Target::Deck));
+        state.targets.push((weapon_rows[1], Target::Weapon));
         for i in 0..4 {
             match current_state.deck.get(i) {
                 None => continue,
                 Some(card) => card
                     .face_down()
+                    .fg(self.settings.theme.card_back())
                     .left_aligned()
                     .render(deck_area[3 - i], buf),
             };
@@ -178,25 +344,63 @@ impl StatefulWidget for &mut App {
         for (i, card) in current_state.open.iter().enumerate() {
             let card_area = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Length(1), Constraint::Fill(1)])
+                .constraints([
+                    Constraint::Length(1),
+                    Constraint::Length(CARD_HEIGHT as u16),
+                    Constraint::Fill(1),
+                ])
                 .split(room_area[i + 2]);
-            format!(" {}", i + 1)
-                .bold()
-                .into_centered_line()
-                .render(card_area[0], buf);
+            state.targets.push((room_area[i + 2], Target::Slot(i)));
+            let key = self.keymap.keys(Command::Play(i)).first();
+            let label = format!(" {}", key.map_or(String::new(), |&key| key_name(key))).bold();
+            match hinted_action {
+                Some(game::Action::Play { slot, .. }) if slot == i => label.yellow().reversed(),
+                _ => label,
+            }
+            .into_centered_line()
+            .render(card_area[0], buf);
             match card {
                 None => continue,
-                Some(c) => c.face_up().render(card_area[1], buf),
+                Some(c) => {
+                    c.face_up()
+                        .fg(self.settings.theme.suit_color(c.suit))
+                        .render(card_area[1], buf);
+                    Paragraph::new(preview(current_state, i, *c))
+                        .centered()
+                        .render(card_area[2], buf);
+                }
             }
         }
 
         match current_state.weapon {
             None => (),
             Some(weapon) => {
-                weapon.face_up().left_aligned().render(weapon_area[1], buf);
+                let weapon_rule = current_state.rules.weapon_rule;
+                match current_state.killed_with_weapon.last() {
+                    None => Line::from("Usable on any monster"),
+                    Some(last_killed) => Line::from(vec![
+                        "Usable on monsters ".into(),
+                        format!("{} {}", weapon_rule.symbol(), last_killed.rank.symbol()).bold(),
+                        format!(" ({weapon_rule} rule)").into(),
+                    ]),
+                }
+                .render(
+                    Rect {
+                        x: weapon_area[1].x,
+                        width: weapon_rows[0].right() - weapon_area[1].x,
+                        ..weapon_rows[0]
+                    },
+                    buf,
+                );
+                weapon
+                    .face_up()
+                    .fg(self.settings.theme.suit_color(weapon.suit))
+                    .left_aligned()
+                    .render(weapon_area[1], buf);
                 for (i, killed) in current_state.killed_with_weapon.iter().enumerate() {
                     killed
                         .face_up()
+                        .fg(self.settings.theme.suit_color(killed.suit))
                         .left_aligned()
                         .render(weapon_area[i + 2], buf);
                 }
@@ -205,86 +409,516 @@ impl StatefulWidget for &mut App {
     }
 }
 
+fn health_after<'a>(label: &'a str, state: &game::State) -> Line<'a> {
+    let health = state.health.to_string();
+    Line::from(vec![
+        label.into(),
+        if state.health <= 0 {
+            health.red().bold()
+        } else {
+            health.green().bold()
+        },
+    ])
+}
+
+// What playing a card would do, worked out by playing it on a copy of the game.
+fn preview<'a>(state: &game::State, slot: usize, card: Card) -> Vec<Line<'a>> {
+    match card.role {
+        Role::Monster => {
+            let barehanded = state.play(slot, false).unwrap();
+            vec![
+                match state.weapon {
+                    None => Line::from("No weapon".dark_gray()),
+                    Some(_) if !state.can_use_weapon(card) => Line::from("Weapon ✗".red()),
+                    Some(_) => health_after("Weapon ", &state.play(slot, true).unwrap()),
+                },
+                health_after("Barehanded ", &barehanded),
+            ]
+        }
+        Role::Potion if state.heals_left == 0 => vec![
+            Line::from("Wasted".red().bold()),
+            Line::from("no heals left".red()),
+        ],
+        Role::Potion => vec![health_after("Heal to ", &state.play(slot, false).unwrap())],
+        Role::Weapon => vec![Line::from(match state.weapon {
+            None => "Equip".to_string(),
+            Some(weapon) => format!("Replaces {}{}", weapon.rank.symbol(), weapon.suit.symbol()),
+        })],
+    }
+}
+
 impl App {
+    pub fn new(rules: Option<RuleSet>, seed: Option<u64>, undo: UndoMode, scoring: Scoring) -> App {
+        App {
+            exit: false,
+            rules,
+            seed,
+            undo,
+            scoring,
+            keymap: Keymap::default(),
+            settings: Settings::default(),
+        }
+    }
+
+    // The key hints shown under the board, with the play keys for the room's
+    // slots grouped together.
+    fn instructions(&self, room_size: usize) -> Vec<(&'static str, String)> {
+        let first_key = |command| {
+            self.keymap
+                .keys(command)
+                .first()
+                .map_or("?".to_string(), |&key| key_name(key))
+        };
+        let play_keys: String = (0..room_size)
+            .map(|slot| first_key(Command::Play(slot)))
+            .collect();
+        [(Command::Play(0).label(), play_keys)]
+            .into_iter()
+            .chain(
+                Command::all()
+                    .into_iter()
+                    .filter(|command| !matches!(command, Command::Play(_)))
+                    .map(|command| (command.label(), first_key(command))),
+            )
+            .collect()
+    }
+
+    // A seed on the command line starts its game straight away, skipping the
+    // menu.
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        let mut state = State {
-            turns: vec![game::State::new()],
-            use_weapon: true,
+        self.keymap = keymap::load().map_err(io::Error::other)?;
+        self.settings = settings::load().unwrap_or_default();
+        let mut screen = match self.seed {
+            None => Screen::Menu(Menu::load()),
+            Some(seed) => self.new_game(self.rules(), Some(seed), self.scoring),
         };
         while !self.exit {
-            terminal.draw(|frame| self.draw(frame, &mut state))?;
-            self.handle_events(&mut state)?;
+            terminal.draw(|frame| self.draw(frame, &mut screen))?;
+            self.handle_events(&mut screen)?;
         }
         Ok(())
     }
 
-    fn draw(&mut self, frame: &mut Frame, state: &mut State) {
-        frame.render_stateful_widget(self, frame.area(), state);
+    fn new_game(&self, rules: RuleSet, seed: Option<u64>, scoring: Scoring) -> Screen {
+        Screen::Game(Box::new(State {
+            turns: vec![match seed {
+                None => game::State::new(rules),
+                Some(seed) => game::State::with_seed(rules, seed),
+            }],
+            use_weapon: self.settings.use_weapon,
+            message: None,
+            hint: None,
+            bot: None,
+            undo: self.undo,
+            undos: 0,
+            scoring,
+            started: Instant::now(),
+            elapsed: Duration::ZERO,
+            targets: Vec::new(),
+            help: false,
+        }))
+    }
+
+    fn resume(&self, save: save::Save) -> Screen {
+        Screen::Game(Box::new(State {
+            turns: save.record.replay().unwrap(),
+            use_weapon: save.use_weapon,
+            message: None,
+            hint: None,
+            bot: None,
+            undo: save.undo,
+            undos: save.undos,
+            scoring: save.scoring,
+            started: Instant::now()
+                .checked_sub(save.elapsed)
+                .unwrap_or_else(Instant::now),
+            elapsed: save.elapsed,
+            targets: Vec::new(),
+            help: false,
+        }))
+    }
+
+    // A finished game goes into the results, and an unfinished one is saved
+    // to be resumed later.
+    fn leave_game(&self, state: &State) -> io::Result<()> {
+        if state.turns.last().unwrap().game_over {
+            save::clear()?;
+            results::append(&results::GameResult::from_state(state))
+        } else {
+            save::store(&save::Save::from_state(state))
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, screen: &mut Screen) {
+        let area = frame.area();
+        match screen {
+            Screen::Menu(menu) => menu::render_menu(menu, area, frame.buffer_mut()),
+            Screen::Seed(seed, message) => {
+                menu::render_seed(seed, message, area, frame.buffer_mut())
+            }
+            Screen::Statistics(statistics) => {
+                menu::render_statistics(statistics, area, frame.buffer_mut())
+            }
+            Screen::Settings(field) => {
+                menu::render_settings(&self.settings, *field, area, frame.buffer_mut())
+            }
+            Screen::Game(state) => {
+                frame.render_stateful_widget(&mut *self, area, &mut **state);
+                if state.help {
+                    self.render_help(area, frame.buffer_mut(), state);
+                }
+            }
+        }
+    }
+
+    fn render_help(&self, area: Rect, buf: &mut Buffer, state: &State) {
+        let rules = &state.turns.last().unwrap().rules;
+        let scoring = match state.scoring {
+            Scoring::Official if rules.endless => {
+                "The rooms you clear plus your remaining health.".to_string()
+            }
+            scoring => scoring.description().to_string(),
+        };
+        let keys = Command::all()
+            .into_iter()
+            .filter(|&command| !matches!(command, Command::Play(slot) if slot > 0))
+            .map(|command| {
+                let keys = match command {
+                    Command::Play(_) => (0..rules.room_size)
+                        .flat_map(|slot| self.keymap.keys(Command::Play(slot)).first())
+                        .map(|&key| key_name(key))
+                        .collect::<Vec<_>>(),
+                    _ => self
+                        .keymap
+                        .keys(command)
+                        .iter()
+                        .map(|&key| key_name(key))
+                        .collect(),
+                };
+                format!("{} {}", command.label(), keys.join(" "))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        let text: Vec<Line> = rules
+            .explain()
+            .into_iter()
+            .chain([("Scoring", scoring), ("Keys", keys)])
+            .flat_map(|(topic, text)| {
+                [
+                    Line::from(vec![topic.bold().yellow(), " ".into(), text.into()]),
+                    Line::default(),
+                ]
+            })
+            .collect();
+
+        let [popup_area] = Layout::vertical([Constraint::Percentage(80)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup_area] = Layout::horizontal([Constraint::Percentage(70)])
+            .flex(Flex::Center)
+            .areas(popup_area);
+        Clear.render(popup_area, buf);
+        Paragraph::new(text)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .title(Line::from(" Rules ".bold()).centered())
+                    .title_bottom(
+                        Line::from(vec![" Close ".into(), "<Esc> ".blue().bold()]).right_aligned(),
+                    )
+                    .border_set(border::THICK)
+                    .padding(Padding::horizontal(1)),
+            )
+            .render(popup_area, buf);
     }
 
     fn exit(&mut self) {
         self.exit = true;
     }
 
-    fn handle_key_event(&mut self, key_event: KeyEvent, state: &mut State) {
-        let current_state = state.turns.last().unwrap();
+    fn apply(&mut self, action: game::Action, state: &mut State) {
+        match state.turns.last().unwrap().apply(action) {
+            Ok(s) => {
+                state.turns.push(s);
+                state.hint = None;
+                state.elapsed = state.started.elapsed();
+            }
+            Err(illegal_move) => state.message = Some(illegal_move.to_string()),
+        }
+    }
 
-        match key_event.code {
-            KeyCode::Char('q') => self.exit(),
-            KeyCode::Char('u') => {
-                if state.turns.len() > 1 {
-                    state.turns.pop();
-                }
+    fn handle_menu_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        menu: &mut Menu,
+    ) -> io::Result<Option<Screen>> {
+        menu.message = None;
+        let item = match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                menu.select(-1);
+                return Ok(None);
             }
-            KeyCode::Char('w') => {
-                state.use_weapon = !state.use_weapon;
+            KeyCode::Down | KeyCode::Char('j') => {
+                menu.select(1);
+                return Ok(None);
             }
-            KeyCode::Char('r') => {
-                if !current_state.game_over {
-                    current_state.run().map(|s| state.turns.push(s));
-                }
+            KeyCode::Enter => menu.selected,
+            KeyCode::Esc | KeyCode::Char('q') => Item::Quit,
+            _ => return Ok(None),
+        };
+        Ok(Some(match item {
+            Item::NewGame => self.new_game(self.rules(), None, self.scoring),
+            Item::Resume => match menu.save.take() {
+                Some(save) => self.resume(save),
+                None => return Ok(None),
+            },
+            Item::PlaySeed => Screen::Seed(String::new(), None),
+            // Everyone gets the same deal for the day, under the official
+            // rules.
+            Item::Daily => {
+                let seed = record::today().replace('-', "").parse().ok();
+                self.new_game(RuleSet::default(), seed, Scoring::Official)
             }
-            KeyCode::Char('1') => {
-                if !current_state.game_over {
-                    current_state
-                        .play(0, state.use_weapon)
-                        .map(|s| state.turns.push(s));
+            Item::Statistics => match results::load() {
+                Ok(results) => Screen::Statistics(results::Statistics::new(&results)),
+                Err(e) => {
+                    menu.message = Some(format!("Cannot read the results: {e}"));
+                    return Ok(None);
                 }
+            },
+            Item::Settings => Screen::Settings(Field::Theme),
+            Item::Quit => {
+                self.exit();
+                return Ok(None);
             }
-            KeyCode::Char('2') => {
-                if !current_state.game_over {
-                    current_state
-                        .play(1, state.use_weapon)
-                        .map(|s| state.turns.push(s));
-                }
+        }))
+    }
+
+    fn handle_seed_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        seed: &mut String,
+        message: &mut Option<String>,
+    ) -> Option<Screen> {
+        *message = None;
+        match key_event.code {
+            KeyCode::Esc => return Some(Screen::Menu(Menu::load())),
+            KeyCode::Enter => match seed.parse() {
+                Ok(seed) => return Some(self.new_game(self.rules(), Some(seed), self.scoring)),
+                Err(_) => *message = Some("Enter a seed number".to_string()),
+            },
+            KeyCode::Backspace => {
+                seed.pop();
             }
-            KeyCode::Char('3') => {
-                if !current_state.game_over {
-                    current_state
-                        .play(2, state.use_weapon)
-                        .map(|s| state.turns.push(s));
-                }
+            KeyCode::Char(c) if c.is_ascii_digit() => seed.push(c),
+            _ => {}
+        }
+        None
+    }
+
+    fn rules(&self) -> RuleSet {
+        self.rules
+            .clone()
+            .unwrap_or_else(|| self.settings.rules.clone())
+    }
+
+    fn change_setting(&mut self, field: Field, offset: isize) {
+        menu::change(&mut self.settings, field, offset);
+        if field == Field::Rules {
+            self.rules = None;
+        }
+    }
+
+    fn handle_settings_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        field: &mut Field,
+    ) -> io::Result<Option<Screen>> {
+        let fields: Vec<Field> = Field::iter().collect();
+        let index = fields.iter().position(|f| f == field).unwrap();
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => *field = fields[index.saturating_sub(1)],
+            KeyCode::Down | KeyCode::Char('j') => {
+                *field = fields[(index + 1).min(fields.len() - 1)]
             }
-            KeyCode::Char('4') => {
-                if !current_state.game_over {
-                    current_state
-                        .play(3, state.use_weapon)
-                        .map(|s| state.turns.push(s));
-                }
+            KeyCode::Left | KeyCode::Char('h') => self.change_setting(*field, -1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => self.change_setting(*field, 1),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                settings::store(&self.settings)?;
+                return Ok(Some(Screen::Menu(Menu::load())));
             }
             _ => {}
         }
+        Ok(None)
+    }
+
+    // Returns whether the player quit the game.
+    fn handle_key_event(&mut self, key_event: KeyEvent, state: &mut State) -> bool {
+        state.message = None;
+
+        let command = self.keymap.command(key_event.code);
+        if state.help {
+            if key_event.code == KeyCode::Esc || command == Some(Command::Help) {
+                state.help = false;
+            }
+            return false;
+        }
+        let Some(command) = command else {
+            return false;
+        };
+        match command {
+            Command::Quit => return true,
+            Command::Undo if state.turns.len() > 1 => self.undo(state),
+            Command::Undo => {}
+            Command::ToggleWeapon => {
+                state.use_weapon = !state.use_weapon;
+            }
+            Command::Run => self.apply(game::Action::Run, state),
+            Command::Hint => self.request_hint(state),
+            Command::Help => state.help = true,
+            Command::Bot => {
+                state.bot = match state.bot {
+                    None => Some((Box::new(Lookahead::default()), Instant::now())),
+                    Some(_) => None,
+                };
+            }
+            Command::Play(slot) => self.apply(
+                game::Action::Play {
+                    slot,
+                    use_weapon: state.use_weapon,
+                },
+                state,
+            ),
+        }
+        false
+    }
+
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent, state: &mut State) {
+        let MouseEventKind::Down(button) = mouse_event.kind else {
+            return;
+        };
+        if state.help {
+            return;
+        }
+
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        let Some(&(_, target)) = state
+            .targets
+            .iter()
+            .find(|(area, _)| area.contains(position))
+        else {
+            return;
+        };
+        state.message = None;
+        match target {
+            Target::Slot(slot) => {
+                let barehanded = button == MouseButton::Right
+                    || mouse_event.modifiers.intersects(
+                        KeyModifiers::SHIFT | KeyModifiers::CONTROL | KeyModifiers::ALT,
+                    );
+                self.apply(
+                    game::Action::Play {
+                        slot,
+                        use_weapon: state.use_weapon && !barehanded,
+                    },
+                    state,
+                );
+            }
+            Target::Deck => self.apply(game::Action::Run, state),
+            Target::Weapon => state.use_weapon = !state.use_weapon,
+        }
+    }
+
+    fn undo(&mut self, state: &mut State) {
+        match state.undo {
+            UndoMode::Ironman => {
+                state.message = Some("Undo is disabled in ironman mode".to_string());
+            }
+            UndoMode::Budget(_) if state.undo.undos_left(state.undos) == Some(0) => {
+                state.message = Some("No undos left".to_string());
+            }
+            _ => {
+                state.turns.pop();
+                state.undos += 1;
+                state.hint = None;
+            }
+        }
     }
 
-    fn handle_events(&mut self, state: &mut State) -> io::Result<()> {
-        match event::read()? {
+    // The lookahead only searches the current room, so a hint never depends
+    // on cards the player cannot see, and it is cheap enough to run on the
+    // key press.
+    fn request_hint(&mut self, state: &mut State) {
+        state.hint = Lookahead::default().choose(state.turns.last().unwrap());
+    }
+
+    fn step_bot(&mut self, state: &mut State) {
+        let Some((bot, moved_at)) = &mut state.bot else {
+            return;
+        };
+        if moved_at.elapsed() < BOT_DELAY {
+            return;
+        }
+
+        match bot.choose(state.turns.last().unwrap()) {
+            Some(action) => {
+                *moved_at = Instant::now();
+                self.apply(action, state);
+            }
+            None => state.bot = None,
+        }
+    }
+
+    fn handle_events(&mut self, screen: &mut Screen) -> io::Result<()> {
+        if let Screen::Game(state) = screen {
+            self.step_bot(state);
+        }
+        // poll with a timeout so that the bot's moves get drawn without waiting
+        // for the next key press.
+        if !event::poll(Duration::from_millis(100))? {
+            return Ok(());
+        }
+        let next = match (event::read()?, &mut *screen) {
             // it's important to check that the event is a key press event as
             // crossterm also emits key release and repeat events on Windows.
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                self.handle_key_event(key_event, state)
+            (Event::Key(key_event), screen) if key_event.kind == KeyEventKind::Press => {
+                self.handle_screen_key_event(key_event, screen)?
             }
-            _ => {}
+            (Event::Mouse(mouse_event), Screen::Game(state)) => {
+                self.handle_mouse_event(mouse_event, state);
+                None
+            }
+            _ => None,
         };
+        if let Some(next) = next {
+            *screen = next;
+        }
         Ok(())
     }
+
+    fn handle_screen_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        screen: &mut Screen,
+    ) -> io::Result<Option<Screen>> {
+        match screen {
+            Screen::Menu(menu) => self.handle_menu_key_event(key_event, menu),
+            Screen::Seed(seed, message) => Ok(self.handle_seed_key_event(key_event, seed, message)),
+            Screen::Statistics(_) => Ok(match key_event.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                    Some(Screen::Menu(Menu::load()))
+                }
+                _ => None,
+            }),
+            Screen::Settings(field) => self.handle_settings_key_event(key_event, field),
+            Screen::Game(state) => {
+                if !self.handle_key_event(key_event, state) {
+                    return Ok(None);
+                }
+                self.leave_game(state)?;
+                Ok(Some(Screen::Menu(Menu::load())))
+            }
+        }
+    }
 }
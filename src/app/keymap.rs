@@ -0,0 +1,271 @@
+use std::{error::Error, fmt, fs, io, path::PathBuf};
+
+use crossterm::event::KeyCode;
+
+use crate::rules::MAX_ROOM_SIZE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Play(usize),
+    ToggleWeapon,
+    Run,
+    Hint,
+    Bot,
+    Undo,
+    Help,
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    pub name: String,
+    bindings: Vec<(Command, Vec<KeyCode>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeymapError {
+    Io(String),
+    UnknownPreset(String),
+    InvalidLine(usize, String),
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::Io(e) => write!(f, "Cannot read the keymap: {e}"),
+            KeymapError::UnknownPreset(name) => write!(f, "Unknown keymap {name}"),
+            KeymapError::InvalidLine(index, line) => {
+                write!(f, "Invalid keymap line {}: {line}", index + 1)
+            }
+        }
+    }
+}
+
+impl Error for KeymapError {}
+
+impl Command {
+    pub fn all() -> Vec<Command> {
+        (0..MAX_ROOM_SIZE)
+            .map(Command::Play)
+            .chain([
+                Command::ToggleWeapon,
+                Command::Run,
+                Command::Hint,
+                Command::Bot,
+                Command::Undo,
+                Command::Help,
+                Command::Quit,
+            ])
+            .collect()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Command::Play(_) => "Play",
+            Command::ToggleWeapon => "Toggle Use Weapon",
+            Command::Run => "Run",
+            Command::Hint => "Hint",
+            Command::Bot => "Bot",
+            Command::Undo => "Undo",
+            Command::Help => "Help",
+            Command::Quit => "Quit",
+        }
+    }
+
+    fn name(self) -> String {
+        match self {
+            Command::Play(slot) => format!("play{}", slot + 1),
+            Command::ToggleWeapon => "weapon".to_string(),
+            Command::Run => "run".to_string(),
+            Command::Hint => "hint".to_string(),
+            Command::Bot => "bot".to_string(),
+            Command::Undo => "undo".to_string(),
+            Command::Help => "help".to_string(),
+            Command::Quit => "quit".to_string(),
+        }
+    }
+}
+
+fn parse_key(s: &str) -> Option<KeyCode> {
+    let mut chars = s.chars();
+    match (s, chars.next(), chars.next()) {
+        ("esc", _, _) => Some(KeyCode::Esc),
+        ("enter", _, _) => Some(KeyCode::Enter),
+        ("tab", _, _) => Some(KeyCode::Tab),
+        ("space", _, _) => Some(KeyCode::Char(' ')),
+        ("backspace", _, _) => Some(KeyCode::Backspace),
+        (_, Some(c), None) => Some(KeyCode::Char(c)),
+        _ => None,
+    }
+}
+
+pub fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_uppercase().to_string(),
+        code => code.to_string(),
+    }
+}
+
+fn chars(keys: &str) -> Vec<KeyCode> {
+    keys.chars().map(KeyCode::Char).collect()
+}
+
+fn path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("scoundrel-solitaire").join("keys"))
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::standard()
+    }
+}
+
+impl Keymap {
+    fn with_slots(name: &str, slots: &str, others: [(Command, &str); 7]) -> Keymap {
+        Keymap {
+            name: name.to_string(),
+            bindings: slots
+                .chars()
+                .enumerate()
+                .map(|(slot, key)| (Command::Play(slot), vec![KeyCode::Char(key)]))
+                .chain(others.map(|(command, keys)| (command, chars(keys))))
+                .collect(),
+        }
+    }
+
+    pub fn standard() -> Keymap {
+        Keymap::with_slots(
+            "standard",
+            "123456789",
+            [
+                (Command::ToggleWeapon, "w"),
+                (Command::Run, "r"),
+                (Command::Hint, "h"),
+                (Command::Bot, "b"),
+                (Command::Undo, "u"),
+                (Command::Help, "?"),
+                (Command::Quit, "q"),
+            ],
+        )
+    }
+
+    pub fn vim() -> Keymap {
+        Keymap::with_slots(
+            "vim",
+            "hjkl;",
+            [
+                (Command::ToggleWeapon, "w"),
+                (Command::Run, "r"),
+                (Command::Hint, "n"),
+                (Command::Bot, "b"),
+                (Command::Undo, "u"),
+                (Command::Help, "?"),
+                (Command::Quit, "q"),
+            ],
+        )
+    }
+
+    pub fn numpad() -> Keymap {
+        Keymap::with_slots(
+            "numpad",
+            "123456789",
+            [
+                (Command::ToggleWeapon, "."),
+                (Command::Run, "0"),
+                (Command::Hint, "+"),
+                (Command::Bot, "*"),
+                (Command::Undo, "-"),
+                (Command::Help, "?"),
+                (Command::Quit, "/q"),
+            ],
+        )
+    }
+
+    pub fn left_hand() -> Keymap {
+        Keymap::with_slots(
+            "lefthand",
+            "asdfg",
+            [
+                (Command::ToggleWeapon, "e"),
+                (Command::Run, "r"),
+                (Command::Hint, "x"),
+                (Command::Bot, "b"),
+                (Command::Undo, "z"),
+                (Command::Help, "?"),
+                (Command::Quit, "q"),
+            ],
+        )
+    }
+
+    pub fn presets() -> Vec<Keymap> {
+        vec![
+            Keymap::standard(),
+            Keymap::vim(),
+            Keymap::numpad(),
+            Keymap::left_hand(),
+        ]
+    }
+
+    pub fn preset(name: &str) -> Option<Keymap> {
+        Keymap::presets()
+            .into_iter()
+            .find(|keymap| keymap.name == name)
+    }
+
+    pub fn command(&self, code: KeyCode) -> Option<Command> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&code))
+            .map(|&(command, _)| command)
+    }
+
+    pub fn keys(&self, command: Command) -> &[KeyCode] {
+        self.bindings
+            .iter()
+            .find(|&&(c, _)| c == command)
+            .map_or(&[], |(_, keys)| keys)
+    }
+
+    // "preset NAME" picks the layout to start from, and "COMMAND KEY..." lines
+    // rebind a command, e.g. "run space" or "play1 a".
+    pub fn parse(text: &str) -> Result<Keymap, KeymapError> {
+        let mut keymap = Keymap::standard();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let invalid = || KeymapError::InvalidLine(index, line.to_string());
+            let mut words = line.split_whitespace();
+            let Some(first) = words.next() else { continue };
+            if first == "preset" {
+                let name = words.next().ok_or_else(invalid)?;
+                keymap = Keymap::preset(name)
+                    .ok_or_else(|| KeymapError::UnknownPreset(name.to_string()))?;
+                continue;
+            }
+
+            let command = Command::all()
+                .into_iter()
+                .find(|command| command.name() == first)
+                .ok_or_else(invalid)?;
+            let keys = words.map(parse_key).collect::<Option<Vec<_>>>();
+            let keys = keys.filter(|keys| !keys.is_empty()).ok_or_else(invalid)?;
+            keymap.bindings.retain(|&(c, _)| c != command);
+            for (_, bound) in &mut keymap.bindings {
+                bound.retain(|key| !keys.contains(key));
+            }
+            keymap.bindings.push((command, keys));
+        }
+        Ok(keymap)
+    }
+}
+
+pub fn load() -> Result<Keymap, KeymapError> {
+    let Some(path) = path() else {
+        return Ok(Keymap::default());
+    };
+    match fs::read_to_string(path) {
+        Ok(text) => Keymap::parse(&text),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Keymap::default()),
+        Err(e) => Err(KeymapError::Io(e.to_string())),
+    }
+}
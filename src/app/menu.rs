@@ -0,0 +1,288 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use tui_big_text::{BigText, PixelSize};
+
+use super::{
+    results::Statistics,
+    save::{self, Save},
+    settings::{Settings, Theme},
+};
+use crate::{card::Suit, rules::RuleSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum Item {
+    NewGame,
+    Resume,
+    PlaySeed,
+    Daily,
+    Statistics,
+    Settings,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum Field {
+    Theme,
+    Rules,
+    UseWeapon,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Menu {
+    pub selected: Item,
+    pub save: Option<Save>,
+    pub message: Option<String>,
+}
+
+impl Item {
+    fn label(self) -> &'static str {
+        match self {
+            Item::NewGame => "New game",
+            Item::Resume => "Resume",
+            Item::PlaySeed => "Play seed",
+            Item::Daily => "Daily challenge",
+            Item::Statistics => "Statistics",
+            Item::Settings => "Settings",
+            Item::Quit => "Quit",
+        }
+    }
+}
+
+impl Field {
+    fn label(self) -> &'static str {
+        match self {
+            Field::Theme => "Theme",
+            Field::Rules => "Rules",
+            Field::UseWeapon => "Use weapon by default",
+        }
+    }
+}
+
+// The entry `step` places after `current`, wrapping around; an entry that is
+// not in the list steps from the start.
+fn cycle<T: Clone + PartialEq>(values: &[T], current: &T, step: isize) -> T {
+    let index = values.iter().position(|value| value == current);
+    let next = match index {
+        None if step > 0 => 0,
+        None => values.len() - 1,
+        Some(index) => (index as isize + step).rem_euclid(values.len() as isize) as usize,
+    };
+    values[next].clone()
+}
+
+fn step<T: Copy + PartialEq>(values: &[T], current: T, step: isize) -> T {
+    cycle(values, &current, step)
+}
+
+impl Menu {
+    // Resume is offered, and selected, when there is a saved game to go back to.
+    pub fn load() -> Menu {
+        let save = save::load().filter(|save| save.record.replay().is_ok());
+        Menu {
+            selected: if save.is_some() {
+                Item::Resume
+            } else {
+                Item::NewGame
+            },
+            save,
+            message: None,
+        }
+    }
+
+    fn enabled(&self, item: Item) -> bool {
+        item != Item::Resume || self.save.is_some()
+    }
+
+    pub fn select(&mut self, offset: isize) {
+        let items: Vec<Item> = Item::iter().filter(|&item| self.enabled(item)).collect();
+        self.selected = step(&items, self.selected, offset);
+    }
+}
+
+pub fn change(settings: &mut Settings, field: Field, offset: isize) {
+    match field {
+        Field::Theme => {
+            settings.theme = step(&Theme::iter().collect::<Vec<_>>(), settings.theme, offset)
+        }
+        Field::Rules => {
+            let presets: Vec<String> = RuleSet::presets().iter().map(|r| r.to_string()).collect();
+            let name = cycle(&presets, &settings.rules.to_string(), offset);
+            settings.rules = RuleSet::preset(&name).expect("presets have their own names");
+        }
+        Field::UseWeapon => settings.use_weapon = !settings.use_weapon,
+    }
+}
+
+fn block<'a>(title: &'a str, keys: &[(&'a str, &'a str)]) -> Block<'a> {
+    let instructions: Vec<Span> = keys
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &(label, key))| {
+            [
+                format!("{}{label} ", if i == 0 { " " } else { " | " }).into(),
+                format!("<{key}>").blue().bold(),
+            ]
+        })
+        .chain([" ".into()])
+        .collect();
+    Block::bordered()
+        .title(Line::from(format!(" {title} ").bold()).centered())
+        .title_bottom(Line::from(instructions).right_aligned())
+        .border_set(border::THICK)
+}
+
+// Renders `lines` centered in the block, under the big title.
+fn render_screen(block: Block, lines: Vec<Line>, area: Rect, buf: &mut Buffer) {
+    let inner = block.inner(area);
+    block.render(area, buf);
+    let [title_area, _, text_area] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(1),
+        Constraint::Length(lines.len() as u16),
+    ])
+    .flex(Flex::Center)
+    .areas(inner);
+    BigText::builder()
+        .pixel_size(PixelSize::Quadrant)
+        .centered()
+        .lines(vec!["Scoundrel".into()])
+        .build()
+        .render(title_area, buf);
+    Paragraph::new(lines).centered().render(text_area, buf);
+}
+
+fn message_line(message: &Option<String>) -> Line<'_> {
+    Line::from(message.as_deref().unwrap_or_default().yellow().bold())
+}
+
+pub fn render_menu(menu: &Menu, area: Rect, buf: &mut Buffer) {
+    let mut lines: Vec<Line> = Item::iter()
+        .map(|item| {
+            let label = match (item, &menu.save) {
+                (Item::Resume, Some(save)) => format!(
+                    "Resume (seed {}, {} moves)",
+                    save.record.seed,
+                    save.record.moves.len()
+                ),
+                _ => item.label().to_string(),
+            };
+            if item == menu.selected {
+                Line::from(format!(" {label} ").yellow().bold().reversed())
+            } else if menu.enabled(item) {
+                Line::from(label)
+            } else {
+                Line::from(label.dark_gray())
+            }
+        })
+        .collect();
+    lines.extend([Line::default(), message_line(&menu.message)]);
+    render_screen(
+        block(
+            "Menu",
+            &[("Select", "↑↓"), ("Choose", "Enter"), ("Quit", "Q")],
+        ),
+        lines,
+        area,
+        buf,
+    );
+}
+
+pub fn render_seed(seed: &str, message: &Option<String>, area: Rect, buf: &mut Buffer) {
+    let lines = vec![
+        Line::from(vec!["Seed ".into(), seed.to_string().bold(), "_".blue()]),
+        Line::default(),
+        message_line(message),
+    ];
+    render_screen(
+        block("Play seed", &[("Play", "Enter"), ("Back", "Esc")]),
+        lines,
+        area,
+        buf,
+    );
+}
+
+pub fn render_statistics(statistics: &Statistics, area: Rect, buf: &mut Buffer) {
+    let row = |label: &'static str, value: String| Line::from(vec![label.into(), value.bold()]);
+    let lines = vec![
+        row("Games played ", statistics.games.to_string()),
+        row(
+            "Wins ",
+            format!("{} ({:.1}%)", statistics.wins, statistics.win_rate()),
+        ),
+        row("Current streak ", statistics.streak.to_string()),
+        row("Best streak ", statistics.best_streak.to_string()),
+        row(
+            "Best score ",
+            statistics
+                .best_score
+                .map_or("-".to_string(), |score| score.to_string()),
+        ),
+        row(
+            "Average score ",
+            statistics
+                .average_score
+                .map_or("-".to_string(), |score| format!("{score:.1}")),
+        ),
+    ];
+    render_screen(block("Statistics", &[("Back", "Esc")]), lines, area, buf);
+}
+
+pub fn render_settings(settings: &Settings, selected: Field, area: Rect, buf: &mut Buffer) {
+    let rows: Vec<(Field, String)> = Field::iter()
+        .map(|field| {
+            let value = match field {
+                Field::Theme => settings.theme.to_string(),
+                Field::Rules => settings.rules.to_string(),
+                Field::UseWeapon => settings.use_weapon.to_string(),
+            };
+            (field, value)
+        })
+        .collect();
+    // Pad both columns so that the rows line up when centered.
+    let label_width = Field::iter()
+        .map(|field| field.label().len())
+        .max()
+        .unwrap();
+    let value_width = rows.iter().map(|(_, value)| value.len()).max().unwrap();
+    let mut lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(field, value)| {
+            let label = format!("{:>label_width$} ", field.label());
+            if field == selected {
+                Line::from(vec![
+                    label.yellow().bold(),
+                    format!("< {value:^value_width$} >")
+                        .yellow()
+                        .bold()
+                        .reversed(),
+                ])
+            } else {
+                Line::from(vec![
+                    label.into(),
+                    format!("  {value:^value_width$}  ").bold(),
+                ])
+            }
+        })
+        .collect();
+    lines.push(Line::default());
+    lines.push(Line::from_iter(Suit::iter().map(|suit| {
+        Span::from(format!(" {} ", suit.symbol())).fg(settings.theme.suit_color(suit))
+    })));
+    render_screen(
+        block(
+            "Settings",
+            &[("Select", "↑↓"), ("Change", "←→"), ("Back", "Esc")],
+        ),
+        lines,
+        area,
+        buf,
+    );
+}
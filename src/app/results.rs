@@ -0,0 +1,155 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use super::{State, UndoMode};
+use crate::{record, scoring::Scoring};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    pub date: String,
+    pub seed: u64,
+    pub ruleset: String,
+    pub scoring: Scoring,
+    pub score: isize,
+    pub won: bool,
+    pub undo: UndoMode,
+    pub undos: usize,
+}
+
+fn path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("scoundrel-solitaire").join("results"))
+}
+
+impl GameResult {
+    pub fn from_state(state: &State) -> GameResult {
+        let last = state.turns.last().unwrap();
+        GameResult {
+            date: record::today(),
+            seed: last.seed,
+            ruleset: last.rules.to_string(),
+            scoring: state.scoring,
+            score: state.score(),
+            won: last.game_over && last.health > 0,
+            undo: state.undo,
+            undos: state.undos,
+        }
+    }
+
+    // Lines written before "won" was recorded count as losses.
+    fn parse(line: &str) -> Option<GameResult> {
+        let mut result = GameResult {
+            date: String::new(),
+            seed: 0,
+            ruleset: String::new(),
+            scoring: Scoring::Official,
+            score: 0,
+            won: false,
+            undo: UndoMode::Casual,
+            undos: 0,
+        };
+        for field in line.split_whitespace() {
+            match field.split_once('=')? {
+                ("date", value) => result.date = value.to_string(),
+                ("seed", value) => result.seed = value.parse().ok()?,
+                ("rules", value) => result.ruleset = value.to_string(),
+                ("scoring", value) => result.scoring = value.parse().ok()?,
+                ("score", value) => result.score = value.parse().ok()?,
+                ("won", value) => result.won = value.parse().ok()?,
+                ("undo", value) => result.undo = value.parse().ok()?,
+                ("undos", value) => result.undos = value.parse().ok()?,
+                _ => return None,
+            }
+        }
+        Some(result)
+    }
+
+    fn format(&self) -> String {
+        format!(
+            "date={} seed={} rules={} scoring={} score={} won={} undo={} undos={}",
+            self.date,
+            self.seed,
+            self.ruleset,
+            self.scoring,
+            self.score,
+            self.won,
+            self.undo,
+            self.undos
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Statistics {
+    pub games: usize,
+    pub wins: usize,
+    pub streak: usize,
+    pub best_streak: usize,
+    // Over the games scored with the official scheme only, as the other
+    // schemes are not comparable.
+    pub best_score: Option<isize>,
+    pub average_score: Option<f64>,
+}
+
+impl Statistics {
+    pub fn new(results: &[GameResult]) -> Statistics {
+        let mut statistics = Statistics {
+            games: results.len(),
+            ..Statistics::default()
+        };
+        for result in results {
+            if result.won {
+                statistics.wins += 1;
+                statistics.streak += 1;
+                statistics.best_streak = statistics.best_streak.max(statistics.streak);
+            } else {
+                statistics.streak = 0;
+            }
+        }
+
+        let official: Vec<isize> = results
+            .iter()
+            .filter(|result| result.scoring == Scoring::Official)
+            .map(|result| result.score)
+            .collect();
+        statistics.best_score = official.iter().copied().max();
+        if !official.is_empty() {
+            statistics.average_score =
+                Some(official.iter().sum::<isize>() as f64 / official.len() as f64);
+        }
+        statistics
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            100.0 * self.wins as f64 / self.games as f64
+        }
+    }
+}
+
+// Unreadable lines are skipped rather than hiding every other result.
+pub fn load() -> io::Result<Vec<GameResult>> {
+    let Some(path) = path() else {
+        return Ok(Vec::new());
+    };
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().filter_map(GameResult::parse).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn append(result: &GameResult) -> io::Result<()> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", result.format())
+}
@@ -0,0 +1,93 @@
+use std::{fs, io, path::PathBuf, time::Duration};
+
+use super::{State, UndoMode};
+use crate::{record::Record, scoring::Scoring};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Save {
+    pub use_weapon: bool,
+    pub undo: UndoMode,
+    pub undos: usize,
+    pub scoring: Scoring,
+    pub elapsed: Duration,
+    pub record: Record,
+}
+
+fn path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("scoundrel-solitaire").join("save"))
+}
+
+impl Save {
+    pub fn from_state(state: &State) -> Save {
+        Save {
+            use_weapon: state.use_weapon,
+            undo: state.undo,
+            undos: state.undos,
+            scoring: state.scoring,
+            elapsed: state.elapsed,
+            record: Record::from_turns(&state.turns),
+        }
+    }
+
+    // The settings lines come before the record, whose headers start with '['.
+    // Older saves may only have some of them.
+    fn parse(contents: &str) -> Option<Save> {
+        let (settings, record) = contents.split_at(contents.find('[')?);
+        let mut use_weapon = None;
+        let mut undo = UndoMode::Casual;
+        let mut undos = 0;
+        let mut scoring = Scoring::Official;
+        let mut elapsed = Duration::ZERO;
+        for line in settings.lines() {
+            match line.split_once(' ')? {
+                ("use_weapon", value) => use_weapon = Some(value.parse().ok()?),
+                ("undo", value) => undo = value.parse().ok()?,
+                ("undos", value) => undos = value.parse().ok()?,
+                ("scoring", value) => scoring = value.parse().ok()?,
+                ("elapsed", value) => elapsed = Duration::from_secs(value.parse().ok()?),
+                _ => return None,
+            }
+        }
+        Some(Save {
+            use_weapon: use_weapon?,
+            undo,
+            undos,
+            scoring,
+            elapsed,
+            record: record.parse().ok()?,
+        })
+    }
+
+    fn format(&self) -> String {
+        format!(
+            "use_weapon {}\nundo {}\nundos {}\nscoring {}\nelapsed {}\n{}",
+            self.use_weapon,
+            self.undo,
+            self.undos,
+            self.scoring,
+            self.elapsed.as_secs(),
+            self.record
+        )
+    }
+}
+
+pub fn load() -> Option<Save> {
+    Save::parse(&fs::read_to_string(path()?).ok()?)
+}
+
+pub fn store(save: &Save) -> io::Result<()> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, save.format())
+}
+
+pub fn clear() -> io::Result<()> {
+    match path().map(fs::remove_file) {
+        Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
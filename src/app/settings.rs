@@ -0,0 +1,109 @@
+use std::{fmt, fs, io, path::PathBuf, str::FromStr};
+
+use ratatui::style::Color;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{card::Suit, rules::RuleSet};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
+pub enum Theme {
+    #[default]
+    Classic,
+    Light,
+    FourColor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub theme: Theme,
+    pub rules: RuleSet,
+    pub use_weapon: bool,
+}
+
+fn path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("scoundrel-solitaire").join("settings"))
+}
+
+impl Theme {
+    pub fn suit_color(self, suit: Suit) -> Color {
+        match (self, suit) {
+            (Theme::Light, Suit::Spades | Suit::Clubs) => Color::Black,
+            (Theme::FourColor, Suit::Diamonds) => Color::Blue,
+            (Theme::FourColor, Suit::Clubs) => Color::Green,
+            _ => suit.color(),
+        }
+    }
+
+    pub fn card_back(self) -> Color {
+        match self {
+            Theme::Light => Color::Gray,
+            _ => Color::DarkGray,
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Theme::Classic => write!(f, "classic"),
+            Theme::Light => write!(f, "light"),
+            Theme::FourColor => write!(f, "four_color"),
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Theme, ()> {
+        Theme::iter().find(|theme| theme.to_string() == s).ok_or(())
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            theme: Theme::default(),
+            rules: RuleSet::default(),
+            use_weapon: true,
+        }
+    }
+}
+
+impl Settings {
+    // One "key value" line per setting; missing lines keep their defaults.
+    fn parse(contents: &str) -> Option<Settings> {
+        let mut settings = Settings::default();
+        for line in contents.lines() {
+            match line.split_once(' ')? {
+                ("theme", value) => settings.theme = value.parse().ok()?,
+                ("rules", value) => settings.rules = value.parse().ok()?,
+                ("use_weapon", value) => settings.use_weapon = value.parse().ok()?,
+                _ => return None,
+            }
+        }
+        Some(settings)
+    }
+
+    fn format(&self) -> String {
+        format!(
+            "theme {}\nrules {}\nuse_weapon {}\n",
+            self.theme, self.rules, self.use_weapon
+        )
+    }
+}
+
+pub fn load() -> Option<Settings> {
+    Settings::parse(&fs::read_to_string(path()?).ok()?)
+}
+
+pub fn store(settings: &Settings) -> io::Result<()> {
+    let Some(path) = path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, settings.format())
+}
@@ -0,0 +1,48 @@
+use std::{fmt, str::FromStr};
+
+const DEFAULT_BUDGET: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UndoMode {
+    #[default]
+    Casual,
+    Budget(usize),
+    Ironman,
+}
+
+impl UndoMode {
+    pub fn undos_left(self, undos: usize) -> Option<usize> {
+        match self {
+            UndoMode::Casual => None,
+            UndoMode::Budget(budget) => Some(budget.saturating_sub(undos)),
+            UndoMode::Ironman => Some(0),
+        }
+    }
+}
+
+impl fmt::Display for UndoMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UndoMode::Casual => write!(f, "casual"),
+            UndoMode::Budget(budget) => write!(f, "budget:{budget}"),
+            UndoMode::Ironman => write!(f, "ironman"),
+        }
+    }
+}
+
+impl FromStr for UndoMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<UndoMode, ()> {
+        match s.split_once(':') {
+            None => match s {
+                "casual" => Ok(UndoMode::Casual),
+                "budget" => Ok(UndoMode::Budget(DEFAULT_BUDGET)),
+                "ironman" => Ok(UndoMode::Ironman),
+                _ => Err(()),
+            },
+            Some(("budget", budget)) => budget.parse().map(UndoMode::Budget).map_err(|_| ()),
+            Some(_) => Err(()),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::{env, ops::Range, process, thread};
+
+use scoundrel::{
+    game::State,
+    rules::RuleSet,
+    solver::Solver,
+    strategy::{Greedy, Lookahead, RandomStrategy, Strategy},
+};
+
+const USAGE: &str = "usage: simulate [--strategy random|greedy|lookahead|solver] \
+                     [--rules PRESET[,OPTION=VALUE...]] [--seeds START..END]";
+const BUCKET_WIDTH: isize = 10;
+const BAR_WIDTH: usize = 50;
+// A full memo can take gigabytes on a hard deal. Capped, each solver stays
+// around 400 MB, and only a few run at once.
+const SOLVER_MEMO_LIMIT: usize = 1_000_000;
+const SOLVER_THREADS: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+enum StrategyKind {
+    Random,
+    Greedy,
+    Lookahead,
+    Solver,
+}
+
+impl StrategyKind {
+    fn build(self, seed: u64) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Random => Box::new(RandomStrategy::new(seed)),
+            StrategyKind::Greedy => Box::new(Greedy),
+            StrategyKind::Lookahead => Box::new(Lookahead::default()),
+            StrategyKind::Solver => Box::new(Solver::with_memo_limit(SOLVER_MEMO_LIMIT)),
+        }
+    }
+}
+
+struct Options {
+    strategy: StrategyKind,
+    rules: RuleSet,
+    seeds: Range<u64>,
+}
+
+struct Outcome {
+    won: bool,
+    score: isize,
+    health: isize,
+    rooms_cleared: usize,
+}
+
+fn parse_seeds(arg: &str) -> Option<Range<u64>> {
+    let (start, end) = arg.split_once("..")?;
+    let seeds = start.parse().ok()?..end.parse().ok()?;
+    if seeds.is_empty() {
+        None
+    } else {
+        Some(seeds)
+    }
+}
+
+fn parse_args() -> Option<Options> {
+    let mut options = Options {
+        strategy: StrategyKind::Random,
+        rules: RuleSet::default(),
+        seeds: 0..1000,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strategy" => {
+                options.strategy = match args.next()?.as_str() {
+                    "random" => StrategyKind::Random,
+                    "greedy" => StrategyKind::Greedy,
+                    "lookahead" => StrategyKind::Lookahead,
+                    "solver" => StrategyKind::Solver,
+                    _ => return None,
+                }
+            }
+            "--rules" => options.rules = args.next()?.parse().ok()?,
+            "--seeds" => options.seeds = parse_seeds(&args.next()?)?,
+            _ => return None,
+        }
+    }
+    if matches!(options.strategy, StrategyKind::Solver) && options.rules.endless {
+        return None;
+    }
+    Some(options)
+}
+
+fn play(kind: StrategyKind, rules: &RuleSet, seed: u64) -> Outcome {
+    let mut state = State::with_seed(rules.clone(), seed);
+    let mut strategy = kind.build(seed);
+
+    while let Some(action) = strategy.choose(&state) {
+        state = state.apply(action).unwrap();
+    }
+
+    Outcome {
+        won: state.health > 0,
+        score: state.score(),
+        health: state.health,
+        rooms_cleared: state.rooms_cleared,
+    }
+}
+
+fn simulate(options: &Options) -> Vec<Outcome> {
+    let seeds = &options.seeds;
+    let mut threads = thread::available_parallelism().map_or(1, |n| n.get());
+    if matches!(options.strategy, StrategyKind::Solver) {
+        threads = threads.min(SOLVER_THREADS);
+    }
+    let threads = threads as u64;
+    let chunk = (seeds.end - seeds.start).div_ceil(threads);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|i| {
+                let start = seeds.start + i * chunk;
+                let end = (start + chunk).min(seeds.end);
+                scope.spawn(move || {
+                    (start..end)
+                        .map(|seed| play(options.strategy, &options.rules, seed))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+fn mean(values: impl Iterator<Item = isize>) -> Option<f64> {
+    let (sum, count) = values.fold((0, 0), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        None
+    } else {
+        Some(sum as f64 / count as f64)
+    }
+}
+
+fn format_mean(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{v:.2}"))
+}
+
+fn report(options: &Options, outcomes: &[Outcome]) {
+    let seeds = &options.seeds;
+    let deals = outcomes.len();
+    let wins = outcomes.iter().filter(|o| o.won).count();
+    let mut scores: Vec<isize> = outcomes.iter().map(|o| o.score).collect();
+    scores.sort();
+
+    println!("Strategy              {:?}", options.strategy);
+    println!("Rules                 {}", options.rules);
+    println!(
+        "Deals                 {deals} (seeds {}..{})",
+        seeds.start, seeds.end
+    );
+    println!(
+        "Win rate              {:.2}% ({wins}/{deals})",
+        100.0 * wins as f64 / deals as f64
+    );
+    println!(
+        "Score                 min {} | mean {} | median {} | max {}",
+        scores[0],
+        format_mean(mean(scores.iter().copied())),
+        scores[deals / 2],
+        scores[deals - 1]
+    );
+    println!(
+        "Avg health at death   {}",
+        format_mean(mean(outcomes.iter().filter(|o| !o.won).map(|o| o.health)))
+    );
+    println!(
+        "Avg rooms cleared     {}",
+        format_mean(mean(outcomes.iter().map(|o| o.rooms_cleared as isize)))
+    );
+
+    println!();
+    println!("Score distribution");
+    let bucket = |score: isize| score.div_euclid(BUCKET_WIDTH);
+    let (first, last) = (bucket(scores[0]), bucket(scores[deals - 1]));
+    let counts: Vec<usize> = (first..=last)
+        .map(|b| scores.iter().filter(|&&s| bucket(s) == b).count())
+        .collect();
+    let most = *counts.iter().max().unwrap();
+    for (b, count) in (first..=last).zip(counts) {
+        println!(
+            "{:>5}..{:<5} {:<width$} {count}",
+            b * BUCKET_WIDTH,
+            (b + 1) * BUCKET_WIDTH - 1,
+            "#".repeat(count * BAR_WIDTH / most),
+            width = BAR_WIDTH
+        );
+    }
+}
+
+fn main() {
+    let Some(options) = parse_args() else {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    };
+
+    let outcomes = simulate(&options);
+    report(&options, &outcomes);
+}
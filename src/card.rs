@@ -1,14 +1,14 @@
-use ratatui::{
-    prelude::Alignment,
-    style::{Color, Style},
-    text::{Line, Span},
-    widgets::Paragraph,
-};
+use std::{fmt, str::FromStr};
+
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-pub const CARD_WIDTH: usize = 11;
+#[cfg(feature = "tui")]
+mod face;
+#[cfg(feature = "tui")]
+pub use face::{CARD_HEIGHT, CARD_WIDTH};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
 pub enum Rank {
     Ace,
     Two,
@@ -23,9 +23,10 @@ pub enum Rank {
     Jack,
     Queen,
     King,
+    Joker,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
 pub enum Suit {
     Spades,
     Hearts,
@@ -49,6 +50,7 @@ impl Rank {
             Rank::Queen => "Q",
             Rank::King => "K",
             Rank::Ace => "A",
+            Rank::Joker => "JK",
         }
     }
 
@@ -67,6 +69,7 @@ impl Rank {
             Rank::Queen => 12,
             Rank::King => 13,
             Rank::Ace => 14,
+            Rank::Joker => 15,
         }
     }
 }
@@ -80,260 +83,56 @@ impl Suit {
             Suit::Clubs => "♣",
         }
     }
-
-    pub fn color(self) -> Color {
-        match self {
-            Suit::Spades => Color::White,
-            Suit::Hearts => Color::Red,
-            Suit::Diamonds => Color::Red,
-            Suit::Clubs => Color::White,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Card {
-    pub rank: Rank,
-    pub suit: Suit,
 }
 
-fn double<'a>(suit: Suit, rank: Option<Rank>, additional_suit: bool, bottom: bool) -> Line<'a> {
-    let mut string = "│".to_owned();
-    string.push_str(&match rank {
-        None => format!(
-            "{}{}",
-            if additional_suit { suit.symbol() } else { " " },
-            " ".repeat(CARD_WIDTH / 2 - 2 - 1)
-        ),
-        Some(rank) => format!(
-            "{}{}",
-            rank.symbol(),
-            " ".repeat(CARD_WIDTH / 2 - 2 - rank.symbol().len())
-        ),
-    });
-    string.push_str(suit.symbol());
-    string.push_str(&" ".repeat(3));
-    string.push_str(suit.symbol());
-    string.push_str(&" ".repeat(CARD_WIDTH / 2 - 2));
-    string.push_str(&"│");
-    if bottom {
-        Line::from(Span::raw(string.chars().rev().collect::<String>()))
-    } else {
-        Line::from(Span::raw(string))
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
+pub enum Role {
+    Monster,
+    Weapon,
+    Potion,
 }
 
-fn single<'a>(suit: Suit, rank: Option<Rank>, additional_suit: bool, bottom: bool) -> Line<'a> {
-    let mut string = "│".to_owned();
-    string.push_str(&match rank {
-        None => format!(
-            "{}{}",
-            if additional_suit { suit.symbol() } else { " " },
-            " ".repeat(CARD_WIDTH / 2 - 1)
-        ),
-        Some(rank) => format!(
-            "{}{}",
-            rank.symbol(),
-            " ".repeat(CARD_WIDTH / 2 - rank.symbol().len())
-        ),
-    });
-    string.push_str(suit.symbol());
-    string.push_str(&" ".repeat(CARD_WIDTH / 2));
-    string.push_str(&"│");
-    if bottom {
-        Line::from(Span::raw(string.chars().rev().collect::<String>()))
-    } else {
-        Line::from(Span::raw(string))
+impl Role {
+    pub fn of(suit: Suit) -> Role {
+        match suit {
+            Suit::Spades | Suit::Clubs => Role::Monster,
+            Suit::Diamonds => Role::Weapon,
+            Suit::Hearts => Role::Potion,
+        }
     }
 }
 
-fn blank<'a>(suit: Suit, rank: Option<Rank>, additional_suit: bool, bottom: bool) -> Line<'a> {
-    let mut string = "│".to_owned();
-    string.push_str(&match rank {
-        None => format!(
-            "{}{}",
-            if additional_suit { suit.symbol() } else { " " },
-            " ".repeat(CARD_WIDTH - 1)
-        ),
-        Some(rank) => format!(
-            "{}{}",
-            rank.symbol(),
-            " ".repeat(CARD_WIDTH - rank.symbol().len())
-        ),
-    });
-    string.push_str(&"│");
-    if bottom {
-        Line::from(Span::raw(string.chars().rev().collect::<String>()))
-    } else {
-        Line::from(Span::raw(string))
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::Monster => write!(f, "monster"),
+            Role::Weapon => write!(f, "weapon"),
+            Role::Potion => write!(f, "potion"),
+        }
     }
 }
 
-impl Card {
-    pub fn face_down<'a>(self) -> Paragraph<'a> {
-        let mut card = Vec::new();
-
-        card.push(Line::from(Span::raw(format!(
-            "╭{}╮",
-            "─".repeat(CARD_WIDTH)
-        ))));
-
-        card.extend(vec![blank(self.suit, None, false, false); 7]);
-
-        card.push(Line::from(Span::raw(format!(
-            "╰{}╯",
-            "─".repeat(CARD_WIDTH)
-        ))));
+impl FromStr for Role {
+    type Err = ();
 
-        Paragraph::new(card)
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(Color::DarkGray))
+    fn from_str(s: &str) -> Result<Role, ()> {
+        Role::iter().find(|role| role.to_string() == s).ok_or(())
     }
+}
 
-    pub fn face_up<'a>(self) -> Paragraph<'a> {
-        let mut card = Vec::new();
-
-        card.push(Line::from(Span::raw(format!(
-            "╭{}╮",
-            "─".repeat(CARD_WIDTH)
-        ))));
-
-        card.extend(match self.rank {
-            Rank::Two => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![single(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false); 3],
-                vec![single(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Three => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![single(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![single(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Four => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![double(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false); 3],
-                vec![double(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Five => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![double(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![double(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Six => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![double(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![double(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![double(self.suit, None, false, false)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Seven => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![double(self.suit, None, true, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![double(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![double(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Eight => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![double(self.suit, None, true, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![double(self.suit, None, false, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![double(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Nine => vec![
-                vec![double(self.suit, Some(self.rank), false, false)],
-                vec![blank(self.suit, None, true, false)],
-                vec![double(self.suit, None, false, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![double(self.suit, None, false, false)],
-                vec![blank(self.suit, None, true, true)],
-                vec![double(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Ten => vec![
-                vec![double(self.suit, Some(self.rank), false, false)],
-                vec![single(self.suit, None, true, false)],
-                vec![double(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![double(self.suit, None, false, false)],
-                vec![single(self.suit, None, true, true)],
-                vec![double(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Jack => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![blank(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![blank(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Queen => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![blank(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![blank(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::King => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![blank(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![blank(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-            Rank::Ace => vec![
-                vec![blank(self.suit, Some(self.rank), false, false)],
-                vec![blank(self.suit, None, true, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![single(self.suit, None, false, false)],
-                vec![blank(self.suit, None, false, false)],
-                vec![blank(self.suit, None, true, true)],
-                vec![blank(self.suit, Some(self.rank), false, true)],
-            ]
-            .concat(),
-        });
-
-        card.push(Line::from(Span::raw(format!(
-            "╰{}╯",
-            "─".repeat(CARD_WIDTH)
-        ))));
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+    pub role: Role,
+}
 
-        Paragraph::new(card)
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(self.suit.color()))
+impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Card {
+        Card {
+            rank,
+            suit,
+            role: Role::of(suit),
+        }
     }
 }
@@ -0,0 +1,271 @@
+use ratatui::{
+    prelude::Alignment,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::{Card, Rank, Role, Suit};
+
+pub const CARD_WIDTH: usize = 11;
+pub const CARD_HEIGHT: usize = 9;
+
+impl Suit {
+    pub fn color(self) -> Color {
+        match self {
+            Suit::Spades => Color::White,
+            Suit::Hearts => Color::Red,
+            Suit::Diamonds => Color::Red,
+            Suit::Clubs => Color::White,
+        }
+    }
+}
+
+fn double<'a>(suit: Suit, rank: Option<Rank>, additional_suit: bool, bottom: bool) -> Line<'a> {
+    let mut string = "│".to_owned();
+    string.push_str(&match rank {
+        None => format!(
+            "{}{}",
+            if additional_suit { suit.symbol() } else { " " },
+            " ".repeat(CARD_WIDTH / 2 - 2 - 1)
+        ),
+        Some(rank) => format!(
+            "{}{}",
+            rank.symbol(),
+            " ".repeat(CARD_WIDTH / 2 - 2 - rank.symbol().len())
+        ),
+    });
+    string.push_str(suit.symbol());
+    string.push_str(&" ".repeat(3));
+    string.push_str(suit.symbol());
+    string.push_str(&" ".repeat(CARD_WIDTH / 2 - 2));
+    string.push('│');
+    if bottom {
+        Line::from(Span::raw(string.chars().rev().collect::<String>()))
+    } else {
+        Line::from(Span::raw(string))
+    }
+}
+
+fn single<'a>(suit: Suit, rank: Option<Rank>, additional_suit: bool, bottom: bool) -> Line<'a> {
+    let mut string = "│".to_owned();
+    string.push_str(&match rank {
+        None => format!(
+            "{}{}",
+            if additional_suit { suit.symbol() } else { " " },
+            " ".repeat(CARD_WIDTH / 2 - 1)
+        ),
+        Some(rank) => format!(
+            "{}{}",
+            rank.symbol(),
+            " ".repeat(CARD_WIDTH / 2 - rank.symbol().len())
+        ),
+    });
+    string.push_str(suit.symbol());
+    string.push_str(&" ".repeat(CARD_WIDTH / 2));
+    string.push('│');
+    if bottom {
+        Line::from(Span::raw(string.chars().rev().collect::<String>()))
+    } else {
+        Line::from(Span::raw(string))
+    }
+}
+
+fn blank<'a>(suit: Suit, rank: Option<Rank>, additional_suit: bool, bottom: bool) -> Line<'a> {
+    let mut string = "│".to_owned();
+    string.push_str(&match rank {
+        None => format!(
+            "{}{}",
+            if additional_suit { suit.symbol() } else { " " },
+            " ".repeat(CARD_WIDTH - 1)
+        ),
+        Some(rank) => format!(
+            "{}{}",
+            rank.symbol(),
+            " ".repeat(CARD_WIDTH - rank.symbol().len())
+        ),
+    });
+    string.push('│');
+    if bottom {
+        Line::from(Span::raw(string.chars().rev().collect::<String>()))
+    } else {
+        Line::from(Span::raw(string))
+    }
+}
+
+impl Card {
+    pub fn face_down<'a>(self) -> Paragraph<'a> {
+        let mut card = Vec::new();
+
+        card.push(Line::from(Span::raw(format!(
+            "╭{}╮",
+            "─".repeat(CARD_WIDTH)
+        ))));
+
+        card.extend(vec![blank(self.suit, None, false, false); 7]);
+
+        card.push(Line::from(Span::raw(format!(
+            "╰{}╯",
+            "─".repeat(CARD_WIDTH)
+        ))));
+
+        Paragraph::new(card)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::DarkGray))
+    }
+
+    pub fn face_up<'a>(self) -> Paragraph<'a> {
+        let mut card = Vec::new();
+
+        card.push(Line::from(Span::raw(format!(
+            "╭{}╮",
+            "─".repeat(CARD_WIDTH)
+        ))));
+
+        card.extend(match self.rank {
+            Rank::Two => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![single(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false); 3],
+                vec![single(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Three => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![single(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![single(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Four => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![double(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false); 3],
+                vec![double(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Five => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![double(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![double(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Six => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![double(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![double(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![double(self.suit, None, false, false)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Seven => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![double(self.suit, None, true, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![double(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![double(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Eight => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![double(self.suit, None, true, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![double(self.suit, None, false, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![double(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Nine => [
+                vec![double(self.suit, Some(self.rank), false, false)],
+                vec![blank(self.suit, None, true, false)],
+                vec![double(self.suit, None, false, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![double(self.suit, None, false, false)],
+                vec![blank(self.suit, None, true, true)],
+                vec![double(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Ten => [
+                vec![double(self.suit, Some(self.rank), false, false)],
+                vec![single(self.suit, None, true, false)],
+                vec![double(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![double(self.suit, None, false, false)],
+                vec![single(self.suit, None, true, true)],
+                vec![double(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Jack => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![blank(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![blank(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Queen => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![blank(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![blank(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::King => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![blank(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![blank(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+            Rank::Ace | Rank::Joker => [
+                vec![blank(self.suit, Some(self.rank), false, false)],
+                vec![blank(self.suit, None, true, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![single(self.suit, None, false, false)],
+                vec![blank(self.suit, None, false, false)],
+                vec![blank(self.suit, None, true, true)],
+                vec![blank(self.suit, Some(self.rank), false, true)],
+            ]
+            .concat(),
+        });
+
+        if self.role != Role::of(self.suit) {
+            card[4] = Line::from(Span::raw(format!(
+                "│{:^width$}│",
+                self.role.to_string(),
+                width = CARD_WIDTH
+            )));
+        }
+
+        card.push(Line::from(Span::raw(format!(
+            "╰{}╯",
+            "─".repeat(CARD_WIDTH)
+        ))));
+
+        Paragraph::new(card)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(self.suit.color()))
+    }
+}
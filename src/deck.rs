@@ -0,0 +1,227 @@
+use std::{error::Error, fmt, fs, path::Path, str::FromStr};
+
+use strum::IntoEnumIterator;
+
+use crate::card::{Card, Rank, Role, Suit};
+
+const STANDARD: &str = "2-A SC\n2-10 HD\n";
+const FULL: &str = "2-A SCHD\n";
+const DOUBLE: &str = "2-A SC x2\n2-10 HD x2\n";
+const JOKERS: &str = "2-A SC\n2-10 HD\njoker SH monster\n";
+const TRAINING: &str = "2-6 SC\n2-10 HD\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeckSpec {
+    pub name: String,
+    pub cards: Vec<Card>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeckError {
+    Io(String, String),
+    InvalidLine(usize, String),
+    Empty,
+    InvalidPath(String),
+    Changed(String),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckError::Io(path, e) => write!(f, "Cannot read deck {path}: {e}"),
+            DeckError::InvalidLine(index, line) => {
+                write!(f, "Invalid deck line {}: {line}", index + 1)
+            }
+            DeckError::Empty => write!(f, "The deck has no cards"),
+            DeckError::InvalidPath(path) => write!(
+                f,
+                "Deck paths cannot contain spaces, commas, quotes, '=' or '@': {path}"
+            ),
+            DeckError::Changed(path) => write!(f, "Deck {path} has changed since it was played"),
+        }
+    }
+}
+
+impl Error for DeckError {}
+
+fn parse_rank(s: &str) -> Option<Rank> {
+    Rank::iter().find(|&rank| rank != Rank::Joker && rank.symbol() == s)
+}
+
+fn parse_ranks(s: &str) -> Option<Vec<Rank>> {
+    if s == "joker" {
+        return Some(vec![Rank::Joker]);
+    }
+    let (low, high) = match s.split_once('-') {
+        Some((low, high)) => (parse_rank(low)?, parse_rank(high)?),
+        None => (parse_rank(s)?, parse_rank(s)?),
+    };
+    Some(
+        Rank::iter()
+            .filter(|&rank| rank != Rank::Joker)
+            .filter(|rank| (low.value()..=high.value()).contains(&rank.value()))
+            .collect(),
+    )
+}
+
+fn parse_suits(s: &str) -> Option<Vec<Suit>> {
+    s.chars()
+        .map(|c| match c {
+            'S' | '♠' => Some(Suit::Spades),
+            'H' | '♥' => Some(Suit::Hearts),
+            'D' | '♦' => Some(Suit::Diamonds),
+            'C' | '♣' => Some(Suit::Clubs),
+            _ => None,
+        })
+        .collect()
+}
+
+// A line lists ranks (a rank, a range like "2-10", or "joker"), suits as
+// letters or symbols, an optional role and an optional count like "x2".
+fn parse_line(line: &str) -> Option<Vec<Card>> {
+    let mut words = line.split_whitespace();
+    let ranks = parse_ranks(words.next()?)?;
+    let suits = parse_suits(words.next()?)?;
+    let mut role: Option<Role> = None;
+    let mut count = 1;
+    for word in words {
+        match word.strip_prefix('x') {
+            Some(n) => count = n.parse().ok()?,
+            None => role = Some(word.parse().ok()?),
+        }
+    }
+
+    let cards: Vec<Card> = ranks
+        .iter()
+        .flat_map(|&rank| suits.iter().map(move |&suit| Card::new(rank, suit)))
+        .map(|card| Card {
+            role: role.unwrap_or(card.role),
+            ..card
+        })
+        .collect();
+    Some(cards.repeat(count))
+}
+
+// FNV-1a over the cards, which stays the same across builds and platforms,
+// unlike the standard library's hasher.
+fn hash(cards: &[Card]) -> u64 {
+    cards
+        .iter()
+        .flat_map(|card| {
+            format!("{}{}{} ", card.rank.symbol(), card.suit.symbol(), card.role).into_bytes()
+        })
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+impl Default for DeckSpec {
+    fn default() -> DeckSpec {
+        DeckSpec::standard()
+    }
+}
+
+impl DeckSpec {
+    fn builtin(name: &str, text: &str) -> DeckSpec {
+        DeckSpec {
+            name: name.to_string(),
+            ..text.parse().expect("built-in decks are valid")
+        }
+    }
+
+    pub fn standard() -> DeckSpec {
+        DeckSpec::builtin("standard", STANDARD)
+    }
+
+    pub fn full() -> DeckSpec {
+        DeckSpec::builtin("full", FULL)
+    }
+
+    pub fn double() -> DeckSpec {
+        DeckSpec::builtin("double", DOUBLE)
+    }
+
+    pub fn jokers() -> DeckSpec {
+        DeckSpec::builtin("jokers", JOKERS)
+    }
+
+    pub fn training() -> DeckSpec {
+        DeckSpec::builtin("training", TRAINING)
+    }
+
+    pub fn presets() -> Vec<DeckSpec> {
+        vec![
+            DeckSpec::standard(),
+            DeckSpec::full(),
+            DeckSpec::double(),
+            DeckSpec::jokers(),
+            DeckSpec::training(),
+        ]
+    }
+
+    pub fn preset(name: &str) -> Option<DeckSpec> {
+        DeckSpec::presets()
+            .into_iter()
+            .find(|deck| deck.name == name)
+    }
+
+    // The name is the path followed by a hash of the cards, so that it can be
+    // written in a rule spec or a record, and a replay notices when the file
+    // has been edited since.
+    pub fn load(path: impl AsRef<Path>) -> Result<DeckSpec, DeckError> {
+        let path = path.as_ref().display().to_string();
+        if path.contains(|c: char| c.is_whitespace() || ",\"=@".contains(c)) {
+            return Err(DeckError::InvalidPath(path));
+        }
+        let text =
+            fs::read_to_string(&path).map_err(|e| DeckError::Io(path.clone(), e.to_string()))?;
+        let deck: DeckSpec = text.parse()?;
+        Ok(DeckSpec {
+            name: format!("{path}@{:016x}", hash(&deck.cards)),
+            ..deck
+        })
+    }
+
+    // A preset name, or else a path to a deck file, optionally followed by the
+    // hash its cards must match.
+    pub fn find(name: &str) -> Result<DeckSpec, DeckError> {
+        if let Some(deck) = DeckSpec::preset(name) {
+            return Ok(deck);
+        }
+        let Some((path, _)) = name.rsplit_once('@') else {
+            return DeckSpec::load(name);
+        };
+        let deck = DeckSpec::load(path)?;
+        if deck.name != name {
+            return Err(DeckError::Changed(path.to_string()));
+        }
+        Ok(deck)
+    }
+}
+
+impl FromStr for DeckSpec {
+    type Err = DeckError;
+
+    fn from_str(text: &str) -> Result<DeckSpec, DeckError> {
+        let mut cards = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            cards.extend(
+                parse_line(line).ok_or_else(|| DeckError::InvalidLine(index, line.to_string()))?,
+            );
+        }
+        if cards.is_empty() {
+            return Err(DeckError::Empty);
+        }
+        // Seeds shuffle the deck from this order, so it must not depend on how
+        // the spec happens to list its cards.
+        cards.sort();
+        Ok(DeckSpec {
+            name: String::new(),
+            cards,
+        })
+    }
+}
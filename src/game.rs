@@ -1,254 +1,470 @@
 use std::{
     cmp::{max, min},
     collections::VecDeque,
+    error::Error,
+    fmt,
+    sync::Arc,
 };
 
-use rand::seq::SliceRandom;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 
-use crate::card::{Card, Rank, Suit};
-use strum::IntoEnumIterator;
+use crate::{
+    card::{Card, Role},
+    rules::{RuleSet, RunPlacement},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Play { slot: usize, use_weapon: bool },
+    Run,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalMove {
+    SlotOutOfRange(usize),
+    EmptySlot(usize),
+    RanLastRoom,
+    RoomInProgress,
+    NoRunsLeft,
+    GameOver,
+}
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllegalMove::SlotOutOfRange(slot) => write!(f, "There is no slot {}", slot + 1),
+            IllegalMove::EmptySlot(slot) => write!(f, "Slot {} is empty", slot + 1),
+            IllegalMove::RanLastRoom => write!(f, "Cannot run twice in a row"),
+            IllegalMove::RoomInProgress => {
+                write!(f, "Cannot run after playing a card in this room")
+            }
+            IllegalMove::NoRunsLeft => write!(f, "There are no runs left"),
+            IllegalMove::GameOver => write!(f, "The game is already over"),
+        }
+    }
+}
+
+impl Error for IllegalMove {}
+
+// A won game has no cards left, so counting the remaining monsters gives the
+// official score at the end and a running score mid-game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreBreakdown {
+    pub depth: isize,
+    pub health: isize,
+    pub monster_penalty: isize,
+    pub potion_bonus: isize,
+}
 
-const MAX_HEALTH: isize = 20;
+impl ScoreBreakdown {
+    pub fn total(&self) -> isize {
+        self.depth + self.health - self.monster_penalty + self.potion_bonus
+    }
+}
+
+impl fmt::Display for ScoreBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.depth > 0 {
+            write!(f, "depth {} + ", self.depth)?;
+        }
+        write!(f, "health {}", self.health)?;
+        if self.monster_penalty > 0 {
+            write!(f, " - monsters {}", self.monster_penalty)?;
+        }
+        if self.potion_bonus > 0 {
+            write!(f, " + potion {}", self.potion_bonus)?;
+        }
+        Ok(())
+    }
+}
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct State {
+    pub rules: Arc<RuleSet>,
+    pub seed: u64,
     played_in_room: usize,
     played: Option<Card>,
     pub health: isize,
-    pub used_heal: bool,
+    pub heals_left: usize,
     pub deck: VecDeque<Card>,
-    pub open: [Option<Card>; 4],
+    pub open: Vec<Option<Card>>,
     pub weapon: Option<Card>,
     pub killed_with_weapon: Vec<Card>,
     pub can_run: bool,
+    pub runs: usize,
+    pub rooms_cleared: usize,
+    pub level: usize,
+    pub last_action: Option<Action>,
     pub game_over: bool,
 }
 
-fn random_deck() -> VecDeque<Card> {
-    let mut cards: Vec<Card> = Rank::iter()
-        .flat_map(|rank| {
-            Suit::iter().map(move |suit| {
-                if [Rank::Ace, Rank::King, Rank::Queen, Rank::Jack].contains(&rank)
-                    && [Suit::Hearts, Suit::Diamonds].contains(&suit)
-                {
-                    None
-                } else {
-                    Some(Card { rank, suit })
-                }
-            })
-        })
-        .flatten()
-        .collect();
-    let mut rng = rand::rng();
-    cards.shuffle(&mut rng);
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Position {
+    played_in_room: usize,
+    health: isize,
+    heals_left: usize,
+    deck: Box<[Card]>,
+    open: Box<[Option<Card>]>,
+    weapon: Option<Card>,
+    last_killed: Option<Card>,
+    can_run: bool,
+    runs: usize,
+    level: usize,
+}
+
+pub fn random_deck<R: Rng + ?Sized>(rules: &RuleSet, rng: &mut R) -> VecDeque<Card> {
+    let mut cards = rules.deck.cards.clone();
+    cards.shuffle(rng);
+    VecDeque::from(cards)
+}
+
+// Every level deeper adds another copy of a strong monster and takes away one
+// of the best potions.
+pub fn escalated_deck<R: Rng + ?Sized>(
+    rules: &RuleSet,
+    level: usize,
+    rng: &mut R,
+) -> VecDeque<Card> {
+    let mut cards = rules.deck.cards.clone();
+    let by_value = |role| {
+        let mut cards: Vec<Card> = cards.iter().filter(|c| c.role == role).copied().collect();
+        cards.sort_by_key(|c| -c.rank.value());
+        cards
+    };
+    let monsters = by_value(Role::Monster);
+    let potions = by_value(Role::Potion);
+    cards.extend(monsters.iter().cycle().take(level));
+    for potion in potions.iter().take(level) {
+        if let Some(index) = cards.iter().position(|c| c == potion) {
+            cards.remove(index);
+        }
+    }
+    cards.shuffle(rng);
     VecDeque::from(cards)
 }
 
+fn fill_room(rules: &RuleSet, deck: &mut VecDeque<Card>, left: Vec<Card>) -> Vec<Option<Card>> {
+    let mut open: Vec<Option<Card>> = left.into_iter().map(Some).collect();
+    while open.len() < rules.room_size {
+        open.push(deck.pop_front());
+    }
+    open
+}
+
+impl Default for State {
+    fn default() -> State {
+        State::new(RuleSet::default())
+    }
+}
+
 impl State {
-    pub fn new() -> State {
-        let mut deck = random_deck();
-        let open = [
-            deck.pop_front(),
-            deck.pop_front(),
-            deck.pop_front(),
-            deck.pop_front(),
-        ];
+    pub fn new(rules: RuleSet) -> State {
+        State::with_seed(rules, rand::random())
+    }
+
+    pub fn with_seed(rules: RuleSet, seed: u64) -> State {
+        let mut deck = random_deck(&rules, &mut StdRng::seed_from_u64(seed));
+        let open = fill_room(&rules, &mut deck, Vec::new());
         State {
+            health: rules.max_health,
+            heals_left: rules.heals_per_room,
+            rules: Arc::new(rules),
+            seed,
             played_in_room: 0,
             played: None,
-            health: MAX_HEALTH,
-            used_heal: false,
             deck,
             open,
             weapon: None,
             killed_with_weapon: Vec::new(),
             can_run: true,
+            runs: 0,
+            rooms_cleared: 0,
+            level: 0,
+            last_action: None,
             game_over: false,
         }
     }
 
-    fn fight(&self, card: Card, use_weapon: bool) -> Option<State> {
-        if ![Suit::Spades, Suit::Clubs].contains(&card.suit) {
-            return None;
-        }
-
-        let can_use_weapon = match self.killed_with_weapon.last() {
-            None => true,
-            Some(last_killed) => card.rank.value() <= last_killed.rank.value(),
-        };
+    pub fn can_use_weapon(&self, card: Card) -> bool {
+        self.weapon.is_some()
+            && match self.killed_with_weapon.last() {
+                None => true,
+                Some(&last_killed) => self.rules.weapon_rule.allows(card, last_killed),
+            }
+    }
 
-        let health: isize;
+    fn fight(&self, card: Card, use_weapon: bool) -> State {
         let mut killed_with_weapon = self.killed_with_weapon.clone();
-        if !use_weapon || self.weapon.is_none() || !can_use_weapon {
-            health = self.health - card.rank.value();
-        } else {
-            health = self.health - max(card.rank.value() - self.weapon.unwrap().rank.value(), 0);
-            killed_with_weapon.push(card);
-        }
+        let health = match self.weapon {
+            Some(weapon) if use_weapon && self.can_use_weapon(card) => {
+                killed_with_weapon.push(card);
+                self.health - max(card.rank.value() - weapon.rank.value(), 0)
+            }
+            _ => self.health - card.rank.value(),
+        };
 
-        Some(State {
+        State {
+            rules: self.rules.clone(),
+            seed: self.seed,
             played_in_room: self.played_in_room,
             played: Some(card),
             health,
-            used_heal: self.used_heal,
+            heals_left: self.heals_left,
             deck: self.deck.clone(),
-            open: self.open,
+            open: self.open.clone(),
             weapon: self.weapon,
             killed_with_weapon,
             can_run: self.can_run,
+            runs: self.runs,
+            rooms_cleared: self.rooms_cleared,
+            level: self.level,
+            last_action: self.last_action,
             game_over: self.game_over,
-        })
-    }
-
-    fn heal(&self, card: Card) -> Option<State> {
-        if !(card.suit == Suit::Hearts) {
-            return None;
         }
+    }
 
-        let new_health;
-        if self.used_heal {
-            new_health = self.health;
+    fn heal(&self, card: Card) -> State {
+        let (new_health, heals_left) = if self.heals_left == 0 {
+            (self.health, 0)
         } else {
-            new_health = min(self.health + card.rank.value(), MAX_HEALTH);
-        }
+            (
+                min(self.health + card.rank.value(), self.rules.heal_cap()),
+                self.heals_left - 1,
+            )
+        };
 
-        Some(State {
+        State {
+            rules: self.rules.clone(),
+            seed: self.seed,
             played_in_room: self.played_in_room,
             played: Some(card),
             health: new_health,
-            used_heal: true,
+            heals_left,
             deck: self.deck.clone(),
-            open: self.open,
+            open: self.open.clone(),
             weapon: self.weapon,
             killed_with_weapon: self.killed_with_weapon.clone(),
             can_run: self.can_run,
+            runs: self.runs,
+            rooms_cleared: self.rooms_cleared,
+            level: self.level,
+            last_action: self.last_action,
             game_over: self.game_over,
-        })
-    }
-
-    fn equip_weapon(&self, card: Card) -> Option<State> {
-        if !(card.suit == Suit::Diamonds) {
-            return None;
         }
+    }
 
-        Some(State {
+    fn equip_weapon(&self, card: Card) -> State {
+        State {
+            rules: self.rules.clone(),
+            seed: self.seed,
             played_in_room: self.played_in_room,
             played: Some(card),
             health: self.health,
-            used_heal: self.used_heal,
+            heals_left: self.heals_left,
             deck: self.deck.clone(),
-            open: self.open,
+            open: self.open.clone(),
             weapon: Some(card),
             killed_with_weapon: Vec::new(),
             can_run: self.can_run,
+            runs: self.runs,
+            rooms_cleared: self.rooms_cleared,
+            level: self.level,
+            last_action: self.last_action,
             game_over: self.game_over,
-        })
+        }
     }
 
-    pub fn play(&self, pos: usize, use_weapon: bool) -> Option<State> {
-        if pos > 3 {
-            return None;
-        }
-        if self.open[pos].is_none() {
-            return None;
+    pub fn play(&self, pos: usize, use_weapon: bool) -> Result<State, IllegalMove> {
+        if self.game_over {
+            return Err(IllegalMove::GameOver);
         }
+        let card = self
+            .open
+            .get(pos)
+            .ok_or(IllegalMove::SlotOutOfRange(pos))?
+            .ok_or(IllegalMove::EmptySlot(pos))?;
 
-        let card = self.open[pos].unwrap();
-        let action = match card.suit {
-            Suit::Spades => self.fight(card, use_weapon),
-            Suit::Hearts => self.heal(card),
-            Suit::Diamonds => self.equip_weapon(card),
-            Suit::Clubs => self.fight(card, use_weapon),
+        let mut new_state = match card.role {
+            Role::Monster => self.fight(card, use_weapon),
+            Role::Potion => self.heal(card),
+            Role::Weapon => self.equip_weapon(card),
         };
-
-        if action.is_none() {
-            return None;
-        }
-
-        let mut new_state = action.unwrap();
         new_state.open[pos] = None;
+        new_state.last_action = Some(Action::Play {
+            slot: pos,
+            use_weapon: new_state.killed_with_weapon.len() > self.killed_with_weapon.len(),
+        });
 
         new_state.played_in_room += 1;
 
-        if new_state.played_in_room == 3 {
-            new_state.open = [
-                new_state.open.iter().flatten().last().copied(),
-                new_state.deck.pop_front(),
-                new_state.deck.pop_front(),
-                new_state.deck.pop_front(),
-            ];
+        if new_state.played_in_room >= self.rules.plays_per_room
+            || new_state.open.iter().all(|c| c.is_none())
+        {
+            let left = new_state.open.iter().flatten().copied().collect();
+            new_state.open = fill_room(&self.rules, &mut new_state.deck, left);
             new_state.played_in_room = 0;
-            new_state.used_heal = false;
+            new_state.rooms_cleared += 1;
+            new_state.heals_left = self.rules.heals_per_room
+                + if self.rules.carry_heals {
+                    min(new_state.heals_left, self.rules.heals_per_room)
+                } else {
+                    0
+                };
             new_state.can_run = true;
         } else {
             new_state.can_run = false;
         }
 
-        if new_state.health <= 0
-            || (new_state.deck.len() <= 0 && new_state.open.iter().all(|c| c.is_none()))
-        {
+        let cleared = new_state.deck.is_empty() && new_state.open.iter().all(|c| c.is_none());
+        if cleared && self.rules.endless && new_state.health > 0 {
+            new_state.level += 1;
+            let mut rng = StdRng::seed_from_u64(self.seed ^ new_state.level as u64);
+            new_state.deck = escalated_deck(&self.rules, new_state.level, &mut rng);
+            new_state.open = fill_room(&self.rules, &mut new_state.deck, Vec::new());
+        } else if new_state.health <= 0 || cleared {
             new_state.game_over = true;
         }
 
-        Some(new_state)
+        Ok(new_state)
+    }
+
+    pub fn runs_left(&self) -> Option<usize> {
+        self.rules
+            .max_runs
+            .map(|max_runs| max_runs.saturating_sub(self.runs))
     }
 
-    pub fn run(&self) -> Option<State> {
+    fn check_run(&self) -> Result<(), IllegalMove> {
+        if self.game_over {
+            return Err(IllegalMove::GameOver);
+        }
+        if self.runs_left() == Some(0) {
+            return Err(IllegalMove::NoRunsLeft);
+        }
         if !self.can_run {
-            return None;
+            return Err(if self.played_in_room > 0 {
+                IllegalMove::RoomInProgress
+            } else {
+                IllegalMove::RanLastRoom
+            });
         }
+        Ok(())
+    }
+
+    pub fn run(&self) -> Result<State, IllegalMove> {
+        self.check_run()?;
 
         let mut deck = self.deck.clone();
-        for card in self.open {
-            match card {
-                None => continue,
-                Some(c) => deck.push_back(c),
-            };
+        // Shuffled runs are seeded from the deal and the run count, so replays
+        // and the solver see the same dungeon.
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(self.runs as u64 + 1));
+        for card in self.open.iter().flatten() {
+            match self.rules.run_placement {
+                RunPlacement::Bottom => deck.push_back(*card),
+                RunPlacement::Shuffle => deck.insert(rng.random_range(0..=deck.len()), *card),
+            }
         }
-        let open = [
-            deck.pop_front(),
-            deck.pop_front(),
-            deck.pop_front(),
-            deck.pop_front(),
-        ];
-        Some(State {
+        let open = fill_room(&self.rules, &mut deck, Vec::new());
+        let health = self.health - self.rules.run_cost;
+        Ok(State {
+            rules: self.rules.clone(),
+            seed: self.seed,
             played_in_room: 0,
             played: None,
-            health: self.health,
-            used_heal: self.used_heal,
+            health,
+            heals_left: self.heals_left,
             deck,
             open,
             weapon: self.weapon,
             killed_with_weapon: self.killed_with_weapon.clone(),
             can_run: false,
-            game_over: self.game_over,
+            runs: self.runs + 1,
+            rooms_cleared: self.rooms_cleared,
+            level: self.level,
+            last_action: Some(Action::Run),
+            game_over: self.game_over || health <= 0,
         })
     }
 
-    pub fn score(&self) -> isize {
-        if self.health <= 0 {
-            [
-                self.deck.iter().collect::<Vec<&Card>>(),
-                self.open.iter().flatten().collect(),
-            ]
-            .concat()
+    pub(crate) fn position(&self) -> Position {
+        Position {
+            played_in_room: self.played_in_room,
+            health: self.health,
+            heals_left: self.heals_left,
+            deck: self.deck.iter().copied().collect(),
+            open: self.open.iter().copied().collect(),
+            weapon: self.weapon,
+            last_killed: self.killed_with_weapon.last().copied(),
+            can_run: self.can_run,
+            runs: self.runs,
+            level: self.level,
+        }
+    }
+
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if self.game_over {
+            return Vec::new();
+        }
+
+        let mut actions = Vec::new();
+        for (slot, card) in self.open.iter().enumerate() {
+            let Some(card) = card else { continue };
+            if card.role == Role::Monster && self.can_use_weapon(*card) {
+                actions.push(Action::Play {
+                    slot,
+                    use_weapon: true,
+                });
+            }
+            actions.push(Action::Play {
+                slot,
+                use_weapon: false,
+            });
+        }
+        if self.check_run().is_ok() {
+            actions.push(Action::Run);
+        }
+        actions
+    }
+
+    pub fn apply(&self, action: Action) -> Result<State, IllegalMove> {
+        match action {
+            Action::Play { slot, use_weapon } => self.play(slot, use_weapon),
+            Action::Run => self.run(),
+        }
+    }
+
+    pub fn score_breakdown(&self) -> ScoreBreakdown {
+        if self.rules.endless {
+            return ScoreBreakdown {
+                depth: self.rooms_cleared as isize,
+                health: self.health,
+                monster_penalty: 0,
+                potion_bonus: 0,
+            };
+        }
+
+        let monster_penalty = self
+            .deck
             .iter()
-            .fold(self.health, |acc, c| {
-                if [Suit::Spades, Suit::Clubs].contains(&c.suit) {
-                    acc - c.rank.value()
-                } else {
-                    acc
-                }
-            })
-        } else {
-            if self.health < 20 {
-                self.health
-            } else {
-                20 + match self.played.unwrap().suit {
-                    Suit::Hearts => self.played.unwrap().rank.value(),
-                    _ => 0,
-                }
+            .chain(self.open.iter().flatten())
+            .filter(|c| c.role == Role::Monster)
+            .map(|c| c.rank.value())
+            .sum();
+        let potion_bonus = match self.played {
+            Some(card) if card.role == Role::Potion && self.health >= self.rules.max_health => {
+                card.rank.value()
             }
+            _ => 0,
+        };
+        ScoreBreakdown {
+            depth: 0,
+            health: self.health,
+            monster_penalty,
+            potion_bonus,
         }
     }
+
+    pub fn score(&self) -> isize {
+        self.score_breakdown().total()
+    }
 }
@@ -0,0 +1,10 @@
+#[cfg(feature = "tui")]
+pub mod app;
+pub mod card;
+pub mod deck;
+pub mod game;
+pub mod record;
+pub mod rules;
+pub mod scoring;
+pub mod solver;
+pub mod strategy;
@@ -1,13 +1,62 @@
-use std::io;
+use std::{env, io, panic, process};
 
-use app::App;
-mod app;
-mod card;
-mod game;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+};
+
+use scoundrel::{
+    app::{App, UndoMode},
+    rules::RuleSet,
+    scoring::Scoring,
+};
+
+fn parse_args() -> Option<App> {
+    let mut rules = None;
+    let mut seed = None;
+    let mut undo = UndoMode::default();
+    let mut scoring = Scoring::default();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => rules = Some(args.next()?.parse().ok()?),
+            "--undo" => undo = args.next()?.parse().ok()?,
+            "--scoring" => scoring = args.next()?.parse().ok()?,
+            _ if seed.is_none() => seed = Some(arg.parse().ok()?),
+            _ => return None,
+        }
+    }
+    Some(App::new(rules, seed, undo, scoring))
+}
 
 fn main() -> io::Result<()> {
+    let Some(mut app) = parse_args() else {
+        let presets: Vec<String> = RuleSet::presets().into_iter().map(|r| r.name).collect();
+        eprintln!(
+            "usage: scoundrel-solitaire [--rules {}[,OPTION=VALUE...]] \
+             [--undo casual|budget[:N]|ironman] \
+             [--scoring official|monsters|weapon|rooms|speed] [SEED]",
+            presets.join("|")
+        );
+        process::exit(2);
+    };
+
     let mut terminal = ratatui::init();
-    let app_result = App::default().run(&mut terminal);
+    // The hook installed by ratatui restores the terminal but leaves mouse
+    // capture on, so it is turned off first.
+    let restore_terminal = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = execute!(io::stdout(), DisableMouseCapture);
+        restore_terminal(info);
+    }));
+    let app_result =
+        execute!(io::stdout(), EnableMouseCapture).and_then(|()| app.run(&mut terminal));
+    let mouse_result = execute!(io::stdout(), DisableMouseCapture);
     ratatui::restore();
-    app_result
+    if let Err(e) = app_result {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+    mouse_result
 }
@@ -0,0 +1,390 @@
+use std::{
+    error::Error,
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    card::Role,
+    game::{Action, IllegalMove, State},
+    rules::{RuleError, RuleSet},
+};
+
+const LINE_WIDTH: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Play(usize),
+    Fight { slot: usize, use_weapon: bool },
+    Run,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub seed: u64,
+    pub ruleset: String,
+    pub date: String,
+    pub moves: Vec<(Move, Option<String>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordError {
+    MissingHeader(&'static str),
+    InvalidHeader(String),
+    InvalidRuleset(RuleError),
+    InvalidMove(String),
+    UnterminatedComment,
+    IllegalMove(usize, IllegalMove),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::MissingHeader(name) => write!(f, "Missing {name} header"),
+            RecordError::InvalidHeader(line) => write!(f, "Invalid header: {line}"),
+            RecordError::InvalidRuleset(e) => write!(f, "Invalid ruleset: {e}"),
+            RecordError::InvalidMove(token) => write!(f, "Invalid move: {token}"),
+            RecordError::UnterminatedComment => write!(f, "Unterminated comment"),
+            RecordError::IllegalMove(index, illegal_move) => {
+                write!(f, "Move {} is illegal: {illegal_move}", index + 1)
+            }
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+impl Move {
+    pub fn action(self) -> Action {
+        match self {
+            Move::Play(slot) => Action::Play {
+                slot,
+                use_weapon: false,
+            },
+            Move::Fight { slot, use_weapon } => Action::Play { slot, use_weapon },
+            Move::Run => Action::Run,
+        }
+    }
+
+    fn from_action(state: &State, action: Action) -> Move {
+        match action {
+            Action::Run => Move::Run,
+            Action::Play { slot, use_weapon } => match state.open[slot] {
+                Some(card) if card.role == Role::Monster => Move::Fight { slot, use_weapon },
+                _ => Move::Play(slot),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::Play(slot) => write!(f, "{}", slot + 1),
+            Move::Fight { slot, use_weapon } => {
+                write!(f, "{}{}", slot + 1, if *use_weapon { "w" } else { "b" })
+            }
+            Move::Run => write!(f, "R"),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = RecordError;
+
+    fn from_str(token: &str) -> Result<Move, RecordError> {
+        let invalid = || RecordError::InvalidMove(token.to_string());
+        if token == "R" {
+            return Ok(Move::Run);
+        }
+
+        let (number, suffix) = token.split_at(token.find(['w', 'b']).unwrap_or(token.len()));
+        let slot = number
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .ok_or_else(invalid)?;
+        match suffix {
+            "" => Ok(Move::Play(slot)),
+            "w" => Ok(Move::Fight {
+                slot,
+                use_weapon: true,
+            }),
+            "b" => Ok(Move::Fight {
+                slot,
+                use_weapon: false,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// Converts days since the Unix epoch to a proleptic Gregorian date, following
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+impl Record {
+    pub fn from_turns(turns: &[State]) -> Record {
+        Record {
+            seed: turns[0].seed,
+            ruleset: turns[0].rules.to_string(),
+            date: today(),
+            moves: turns
+                .windows(2)
+                .filter_map(|pair| {
+                    let action = pair[1].last_action?;
+                    Some((Move::from_action(&pair[0], action), None))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn replay(&self) -> Result<Vec<State>, RecordError> {
+        let rules = self
+            .ruleset
+            .parse::<RuleSet>()
+            .map_err(RecordError::InvalidRuleset)?;
+
+        let mut turns = vec![State::with_seed(rules, self.seed)];
+        for (index, (mv, _)) in self.moves.iter().enumerate() {
+            let next = turns
+                .last()
+                .unwrap()
+                .apply(mv.action())
+                .map_err(|e| RecordError::IllegalMove(index, e))?;
+            turns.push(next);
+        }
+        Ok(turns)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[Seed \"{}\"]", self.seed)?;
+        writeln!(f, "[Ruleset \"{}\"]", self.ruleset)?;
+        writeln!(f, "[Date \"{}\"]", self.date)?;
+        writeln!(f)?;
+
+        let mut line = String::new();
+        for (mv, comment) in &self.moves {
+            let token = match comment {
+                None => mv.to_string(),
+                Some(comment) => format!("{mv} {{{}}}", escape(comment)),
+            };
+            if !line.is_empty() && line.len() + 1 + token.len() > LINE_WIDTH {
+                writeln!(f, "{line}")?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        if !line.is_empty() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+// Comments are kept on one line between braces, so backslashes, closing braces
+// and line breaks in them are escaped.
+fn escape(comment: &str) -> String {
+    let mut escaped = String::new();
+    for c in comment.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '}' => escaped.push_str("\\}"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Reads an escaped comment up to its closing brace, returning it and the rest
+// of the text after the brace.
+fn unescape(text: &str) -> Result<(String, &str), RecordError> {
+    let mut comment = String::new();
+    let mut chars = text.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '}' => return Ok((comment, &text[index + 1..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => comment.push('\n'),
+                Some((_, 'r')) => comment.push('\r'),
+                Some((_, c)) => comment.push(c),
+                None => break,
+            },
+            _ => comment.push(c),
+        }
+    }
+    Err(RecordError::UnterminatedComment)
+}
+
+fn parse_header(line: &str) -> Option<(&str, &str)> {
+    let (name, value) = line.strip_prefix('[')?.strip_suffix(']')?.split_once(' ')?;
+    Some((name, value.strip_prefix('"')?.strip_suffix('"')?))
+}
+
+impl FromStr for Record {
+    type Err = RecordError;
+
+    fn from_str(text: &str) -> Result<Record, RecordError> {
+        let mut seed = None;
+        let mut ruleset = None;
+        let mut date = None;
+
+        let mut lines = text.lines().map(str::trim).peekable();
+        while let Some(line) = lines.next_if(|line| line.starts_with('[')) {
+            let invalid = || RecordError::InvalidHeader(line.to_string());
+            match parse_header(line).ok_or_else(invalid)? {
+                ("Seed", value) => seed = Some(value.parse().map_err(|_| invalid())?),
+                ("Ruleset", value) => ruleset = Some(value.to_string()),
+                ("Date", value) => date = Some(value.to_string()),
+                _ => return Err(invalid()),
+            }
+        }
+
+        let body = lines.collect::<Vec<_>>().join("\n");
+        let mut moves: Vec<(Move, Option<String>)> = Vec::new();
+        let mut rest = body.trim_start();
+        while !rest.is_empty() {
+            if let Some(comment) = rest.strip_prefix('{') {
+                let (text, remainder) = unescape(comment)?;
+                let (_, last_comment) = moves
+                    .last_mut()
+                    .ok_or_else(|| RecordError::InvalidMove(rest.to_string()))?;
+                *last_comment = Some(text);
+                rest = remainder.trim_start();
+            } else {
+                let end = rest.find(|c: char| c.is_whitespace() || c == '{');
+                let (token, remainder) = rest.split_at(end.unwrap_or(rest.len()));
+                moves.push((token.parse()?, None));
+                rest = remainder.trim_start();
+            }
+        }
+
+        Ok(Record {
+            seed: seed.ok_or(RecordError::MissingHeader("Seed"))?,
+            ruleset: ruleset.ok_or(RecordError::MissingHeader("Ruleset"))?,
+            date: date.ok_or(RecordError::MissingHeader("Date"))?,
+            moves,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{Lookahead, Strategy};
+
+    fn play(rules: RuleSet, seed: u64) -> Vec<State> {
+        let mut turns = vec![State::with_seed(rules, seed)];
+        let mut strategy = Lookahead::default();
+        while let Some(action) = strategy.choose(turns.last().unwrap()) {
+            let next = turns.last().unwrap().apply(action).unwrap();
+            turns.push(next);
+        }
+        turns
+    }
+
+    fn assert_same_turns(left: &[State], right: &[State]) {
+        assert_eq!(left.len(), right.len());
+        for (left, right) in left.iter().zip(right) {
+            assert_eq!(left.position(), right.position());
+            assert_eq!(left.last_action, right.last_action);
+            assert_eq!(left.game_over, right.game_over);
+            assert_eq!(left.score(), right.score());
+        }
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        for spec in [
+            "official",
+            "royals",
+            "small_rooms,weapon=strict,run_to=shuffle",
+        ] {
+            for seed in 0..10 {
+                let turns = play(spec.parse().unwrap(), seed);
+                let mut record = Record::from_turns(&turns);
+                for (index, (_, comment)) in record.moves.iter_mut().enumerate() {
+                    *comment = match index % 5 {
+                        0 => Some(format!("move {index}, with a weapon?")),
+                        1 => Some("a}b {nested} \\}".to_string()),
+                        2 => Some(" two\nlines\r\n ".to_string()),
+                        _ => None,
+                    };
+                }
+
+                let text = record.to_string();
+                assert!(text.lines().all(|line| line.len() <= LINE_WIDTH));
+                let parsed: Record = text.parse().unwrap();
+                assert_eq!(parsed, record);
+                assert_eq!(parsed.to_string(), text);
+                assert_same_turns(&parsed.replay().unwrap(), &turns);
+            }
+        }
+    }
+
+    #[test]
+    fn parses_hand_written_records() {
+        let text = "[Seed \"7\"]\n[Ruleset \"official\"]\n[Date \"2025-01-01\"]\n\n\
+                    R {fleeing}\n1b{no weapon yet}   2\n";
+        let record: Record = text.parse().unwrap();
+        assert_eq!(
+            record.moves,
+            vec![
+                (Move::Run, Some("fleeing".to_string())),
+                (
+                    Move::Fight {
+                        slot: 0,
+                        use_weapon: false
+                    },
+                    Some("no weapon yet".to_string())
+                ),
+                (Move::Play(1), None),
+            ]
+        );
+        assert_eq!(record.to_string().parse::<Record>().unwrap(), record);
+    }
+
+    #[test]
+    fn rejects_broken_records() {
+        let header = "[Seed \"7\"]\n[Ruleset \"official\"]\n[Date \"2025-01-01\"]\n\n";
+        assert_eq!(
+            format!("{header}1b {{open").parse::<Record>(),
+            Err(RecordError::UnterminatedComment)
+        );
+        assert_eq!(
+            format!("{header}1x").parse::<Record>(),
+            Err(RecordError::InvalidMove("1x".to_string()))
+        );
+        assert_eq!(
+            "[Seed \"7\"]\n".parse::<Record>(),
+            Err(RecordError::MissingHeader("Ruleset"))
+        );
+    }
+}
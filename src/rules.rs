@@ -0,0 +1,485 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use strum::IntoEnumIterator;
+
+use crate::{
+    card::{Card, Rank, Role, Suit},
+    deck::{DeckError, DeckSpec},
+};
+
+// Each slot of a room is played with a number key, 1 to 9.
+pub const MAX_ROOM_SIZE: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaponRule {
+    Inclusive,
+    Strict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunPlacement {
+    Bottom,
+    Shuffle,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSet {
+    pub name: String,
+    pub max_health: isize,
+    pub room_size: usize,
+    pub plays_per_room: usize,
+    pub weapon_rule: WeaponRule,
+    pub heals_per_room: usize,
+    pub overheal: isize,
+    pub carry_heals: bool,
+    pub max_runs: Option<usize>,
+    pub run_cost: isize,
+    pub run_placement: RunPlacement,
+    pub endless: bool,
+    pub deck: DeckSpec,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    UnknownPreset(String),
+    UnknownOption(String),
+    InvalidValue(String, String),
+    InvalidDeck(DeckError),
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::UnknownPreset(name) => write!(f, "Unknown ruleset {name}"),
+            RuleError::UnknownOption(key) => write!(f, "Unknown rule option {key}"),
+            RuleError::InvalidValue(key, value) => {
+                write!(f, "Invalid value {value} for rule option {key}")
+            }
+            RuleError::InvalidDeck(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for RuleError {}
+
+impl WeaponRule {
+    pub fn allows(self, monster: Card, last_killed: Card) -> bool {
+        match self {
+            WeaponRule::Inclusive => monster.rank.value() <= last_killed.rank.value(),
+            WeaponRule::Strict => monster.rank.value() < last_killed.rank.value(),
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            WeaponRule::Inclusive => "≤",
+            WeaponRule::Strict => "<",
+        }
+    }
+}
+
+impl fmt::Display for WeaponRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeaponRule::Inclusive => write!(f, "inclusive"),
+            WeaponRule::Strict => write!(f, "strict"),
+        }
+    }
+}
+
+impl FromStr for WeaponRule {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<WeaponRule, ()> {
+        match s {
+            "inclusive" => Ok(WeaponRule::Inclusive),
+            "strict" => Ok(WeaponRule::Strict),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for RunPlacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunPlacement::Bottom => write!(f, "bottom"),
+            RunPlacement::Shuffle => write!(f, "shuffle"),
+        }
+    }
+}
+
+impl FromStr for RunPlacement {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<RunPlacement, ()> {
+        match s {
+            "bottom" => Ok(RunPlacement::Bottom),
+            "shuffle" => Ok(RunPlacement::Shuffle),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> RuleSet {
+        RuleSet::official()
+    }
+}
+
+impl RuleSet {
+    pub fn official() -> RuleSet {
+        RuleSet {
+            name: "official".to_string(),
+            max_health: 20,
+            room_size: 4,
+            plays_per_room: 3,
+            weapon_rule: WeaponRule::Inclusive,
+            heals_per_room: 1,
+            overheal: 0,
+            carry_heals: false,
+            max_runs: None,
+            run_cost: 0,
+            run_placement: RunPlacement::Bottom,
+            endless: false,
+            deck: DeckSpec::standard(),
+        }
+    }
+
+    pub fn forgiving() -> RuleSet {
+        RuleSet {
+            name: "forgiving".to_string(),
+            max_health: 25,
+            ..RuleSet::official()
+        }
+    }
+
+    pub fn royals() -> RuleSet {
+        RuleSet {
+            name: "royals".to_string(),
+            deck: DeckSpec::full(),
+            ..RuleSet::official()
+        }
+    }
+
+    pub fn large_rooms() -> RuleSet {
+        RuleSet {
+            name: "large_rooms".to_string(),
+            room_size: 5,
+            plays_per_room: 4,
+            ..RuleSet::official()
+        }
+    }
+
+    pub fn small_rooms() -> RuleSet {
+        RuleSet {
+            name: "small_rooms".to_string(),
+            room_size: 3,
+            plays_per_room: 2,
+            ..RuleSet::official()
+        }
+    }
+
+    pub fn endless() -> RuleSet {
+        RuleSet {
+            name: "endless".to_string(),
+            endless: true,
+            ..RuleSet::official()
+        }
+    }
+
+    pub fn presets() -> Vec<RuleSet> {
+        vec![
+            RuleSet::official(),
+            RuleSet::forgiving(),
+            RuleSet::royals(),
+            RuleSet::large_rooms(),
+            RuleSet::small_rooms(),
+            RuleSet::endless(),
+        ]
+    }
+
+    pub fn preset(name: &str) -> Option<RuleSet> {
+        RuleSet::presets()
+            .into_iter()
+            .find(|rules| rules.name == name)
+    }
+
+    pub fn heal_cap(&self) -> isize {
+        self.max_health + self.overheal
+    }
+
+    // The cards of the deck that play a role, by suit with runs of ranks
+    // joined, e.g. "♠ 2-A, ♥ JK", and the values of the face cards among them.
+    fn cards_with_role(&self, role: Role) -> Option<(String, String)> {
+        let mut ranks: Vec<Rank> = Vec::new();
+        let suits: Vec<String> = Suit::iter()
+            .filter_map(|suit| {
+                let mut values: Vec<Rank> = self
+                    .deck
+                    .cards
+                    .iter()
+                    .filter(|card| card.role == role && card.suit == suit)
+                    .map(|card| card.rank)
+                    .collect();
+                values.sort_by_key(|rank| rank.value());
+                values.dedup();
+                ranks.extend(&values);
+
+                let mut runs: Vec<(Rank, Rank)> = Vec::new();
+                for rank in values {
+                    match runs.last_mut() {
+                        Some((_, last))
+                            if rank != Rank::Joker && rank.value() == last.value() + 1 =>
+                        {
+                            *last = rank
+                        }
+                        _ => runs.push((rank, rank)),
+                    }
+                }
+                let runs: Vec<String> = runs
+                    .into_iter()
+                    .map(|(first, last)| {
+                        if first == last {
+                            first.symbol().to_string()
+                        } else {
+                            format!("{}-{}", first.symbol(), last.symbol())
+                        }
+                    })
+                    .collect();
+                (!runs.is_empty()).then(|| format!("{} {}", suit.symbol(), runs.join(" ")))
+            })
+            .collect();
+        if suits.is_empty() {
+            return None;
+        }
+
+        ranks.sort_by_key(|rank| rank.value());
+        ranks.dedup();
+        let faces: Vec<String> = ranks
+            .into_iter()
+            .filter(|rank| rank.value() > 10)
+            .map(|rank| format!("{} {}", rank.symbol(), rank.value()))
+            .collect();
+        let faces = if faces.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", faces.join(", "))
+        };
+        Some((suits.join(", "), faces))
+    }
+
+    // A plain-language summary of these rules, one paragraph per topic.
+    pub fn explain(&self) -> Vec<(&'static str, String)> {
+        let heals = match (self.heals_per_room, self.carry_heals) {
+            (1, false) => "Only the first potion in a room heals.".to_string(),
+            (n, false) => format!("Only the first {n} potions in a room heal."),
+            (1, true) => "Only one potion per room heals, and an unused heal carries over to \
+                          the next room."
+                .to_string(),
+            (n, true) => format!(
+                "Only {n} potions per room heal, and unused heals carry over to the next room."
+            ),
+        };
+        let monsters = match self.cards_with_role(Role::Monster) {
+            None => format!(
+                "The dungeon has no monsters. You start with {} health.",
+                self.max_health
+            ),
+            Some((cards, faces)) => format!(
+                "Monsters are {cards}, worth their rank{faces}. Fighting one barehanded costs \
+                 its full value in health; you start with {}.",
+                self.max_health
+            ),
+        };
+        let weapons = match self.cards_with_role(Role::Weapon) {
+            None => "The dungeon has no weapons.".to_string(),
+            Some((cards, faces)) => format!(
+                "Weapons are {cards}, worth their rank{faces}. Equipping one replaces the old \
+                 weapon. Fighting with a weapon costs only the monster's value minus the \
+                 weapon's."
+            ),
+        };
+        let potions = match self.cards_with_role(Role::Potion) {
+            None => "The dungeon has no potions.".to_string(),
+            Some((cards, _)) if self.heals_per_room == 0 => {
+                format!("Potions are {cards}, but none of them heal; they are only discarded.")
+            }
+            Some((cards, faces)) => format!(
+                "Potions are {cards}, healing their rank{faces}, up to {} health. {heals}",
+                self.heal_cap()
+            ),
+        };
+        let running = match self.max_runs {
+            Some(0) => "Running is disabled.".to_string(),
+            max_runs => [
+                Some(
+                    "Before playing a card you may run, putting the room back into the dungeon, \
+                     but never twice in a row."
+                        .to_string(),
+                ),
+                max_runs.map(|runs| format!("You can run {runs} time(s) per game.")),
+                (self.run_cost > 0).then(|| format!("Each run costs {} health.", self.run_cost)),
+                (self.run_placement == RunPlacement::Shuffle)
+                    .then(|| "The room is shuffled back in at random.".to_string()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" "),
+        };
+
+        let mut topics = vec![
+            (
+                "Rooms",
+                format!(
+                    "Each room deals {} cards. Once you play {} of them, the rest stay for \
+                     the next room.",
+                    self.room_size, self.plays_per_room
+                ),
+            ),
+            ("Monsters", monsters),
+            ("Weapons", weapons),
+            (
+                "Degradation",
+                format!(
+                    "Once a weapon has killed a monster, it can only be used on monsters {} \
+                     the last one it killed.",
+                    match self.weapon_rule {
+                        WeaponRule::Inclusive => "weaker than or equal to",
+                        WeaponRule::Strict => "strictly weaker than",
+                    }
+                ),
+            ),
+            ("Potions", potions),
+            ("Running", running),
+        ];
+        if self.endless {
+            topics.push((
+                "Endless",
+                "Clearing the dungeon shuffles in a harder one, with more strong monsters \
+                 and fewer potions."
+                    .to_string(),
+            ));
+        }
+        topics
+    }
+
+    pub fn options(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("max_health", self.max_health.to_string()),
+            ("room_size", self.room_size.to_string()),
+            ("plays_per_room", self.plays_per_room.to_string()),
+            ("weapon", self.weapon_rule.to_string()),
+            ("heals_per_room", self.heals_per_room.to_string()),
+            ("overheal", self.overheal.to_string()),
+            ("carry_heals", self.carry_heals.to_string()),
+            (
+                "runs",
+                self.max_runs
+                    .map_or("unlimited".to_string(), |runs| runs.to_string()),
+            ),
+            ("run_cost", self.run_cost.to_string()),
+            ("run_to", self.run_placement.to_string()),
+            ("endless", self.endless.to_string()),
+            ("deck", self.deck.name.clone()),
+        ]
+    }
+
+    // Sets one option, leaving the rules unchanged if the value is invalid or
+    // would leave more plays per room than cards in it.
+    pub fn set_option(&mut self, key: &str, value: &str) -> Result<(), RuleError> {
+        let mut rules = self.clone();
+        rules.set(key, value)?;
+        rules.check()?;
+        *self = rules;
+        Ok(())
+    }
+
+    fn check(&self) -> Result<(), RuleError> {
+        if self.plays_per_room > self.room_size {
+            return Err(RuleError::InvalidValue(
+                "plays_per_room".to_string(),
+                self.plays_per_room.to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), RuleError> {
+        let invalid = || RuleError::InvalidValue(key.to_string(), value.to_string());
+        match key {
+            "max_health" => {
+                self.max_health = value.parse().ok().filter(|&n| n > 0).ok_or_else(invalid)?
+            }
+            "room_size" => {
+                self.room_size = value
+                    .parse()
+                    .ok()
+                    .filter(|n| (1..=MAX_ROOM_SIZE).contains(n))
+                    .ok_or_else(invalid)?
+            }
+            "plays_per_room" => {
+                self.plays_per_room = value
+                    .parse()
+                    .ok()
+                    .filter(|n| (1..=MAX_ROOM_SIZE).contains(n))
+                    .ok_or_else(invalid)?
+            }
+            "weapon" => self.weapon_rule = value.parse().map_err(|_| invalid())?,
+            "heals_per_room" => self.heals_per_room = value.parse().map_err(|_| invalid())?,
+            "overheal" => {
+                self.overheal = value.parse().ok().filter(|&n| n >= 0).ok_or_else(invalid)?
+            }
+            "carry_heals" => self.carry_heals = value.parse().map_err(|_| invalid())?,
+            "runs" => {
+                self.max_runs = match value {
+                    "unlimited" => None,
+                    _ => Some(value.parse().map_err(|_| invalid())?),
+                }
+            }
+            "run_cost" => {
+                self.run_cost = value.parse().ok().filter(|&n| n >= 0).ok_or_else(invalid)?
+            }
+            "run_to" => self.run_placement = value.parse().map_err(|_| invalid())?,
+            "endless" => self.endless = value.parse().map_err(|_| invalid())?,
+            "deck" => self.deck = DeckSpec::find(value).map_err(RuleError::InvalidDeck)?,
+            _ => return Err(RuleError::UnknownOption(key.to_string())),
+        }
+        Ok(())
+    }
+}
+
+// A ruleset is written as its preset name followed by the options that differ
+// from the preset, e.g. "official,weapon=strict".
+impl fmt::Display for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        let preset = RuleSet::preset(&self.name).unwrap_or_default().options();
+        for (option, default) in self.options().into_iter().zip(preset) {
+            if option != default {
+                write!(f, ",{}={}", option.0, option.1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<RuleSet, RuleError> {
+        let mut parts = s.split(',');
+        let name = parts.next().unwrap_or_default();
+        let mut rules =
+            RuleSet::preset(name).ok_or_else(|| RuleError::UnknownPreset(name.to_string()))?;
+        for part in parts {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| RuleError::UnknownOption(part.to_string()))?;
+            rules.set(key, value)?;
+        }
+        // Checked once all options are set, so that they can come in any order.
+        rules.check()?;
+        Ok(rules)
+    }
+}
@@ -0,0 +1,105 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    card::{Card, Role},
+    game::{Action, State},
+};
+
+const TIME_LIMIT: Duration = Duration::from_secs(600);
+const SECONDS_PER_POINT: u64 = 10;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumIter)]
+pub enum Scoring {
+    #[default]
+    Official,
+    MonstersSlain,
+    WeaponEfficiency,
+    RoomsCleared,
+    Speed,
+}
+
+// The monsters fought in each turn, whether the weapon was used, the health
+// lost and the health left afterwards.
+fn fights(turns: &[State]) -> impl Iterator<Item = (Card, bool, isize, isize)> + '_ {
+    turns
+        .windows(2)
+        .filter_map(|pair| match pair[1].last_action? {
+            Action::Play { slot, use_weapon } => {
+                let card = pair[0].open[slot]?;
+                (card.role == Role::Monster).then_some((
+                    card,
+                    use_weapon,
+                    pair[0].health - pair[1].health,
+                    pair[1].health,
+                ))
+            }
+            Action::Run => None,
+        })
+}
+
+impl Scoring {
+    pub fn description(self) -> &'static str {
+        match self {
+            Scoring::Official => {
+                "If you die, your health minus the monsters left in the dungeon. If you \
+                 survive, your health, plus the last potion if you played it at full health."
+            }
+            Scoring::MonstersSlain => "The total value of the monsters you slay.",
+            Scoring::WeaponEfficiency => "The total damage your weapons block.",
+            Scoring::RoomsCleared => "The number of rooms you clear.",
+            Scoring::Speed => {
+                "The official score, plus a point for every 10 seconds a win takes under \
+                 ten minutes."
+            }
+        }
+    }
+
+    pub fn score(self, turns: &[State], elapsed: Duration) -> isize {
+        let last = turns.last().unwrap();
+        match self {
+            Scoring::Official => last.score(),
+            Scoring::MonstersSlain => fights(turns)
+                .filter(|&(_, _, _, health)| health > 0)
+                .map(|(card, _, _, _)| card.rank.value())
+                .sum(),
+            Scoring::WeaponEfficiency => fights(turns)
+                .filter(|&(_, use_weapon, _, _)| use_weapon)
+                .map(|(card, _, damage, _)| card.rank.value() - damage)
+                .sum(),
+            Scoring::RoomsCleared => last.rooms_cleared as isize,
+            Scoring::Speed => {
+                let bonus = TIME_LIMIT.saturating_sub(elapsed).as_secs() / SECONDS_PER_POINT;
+                if last.game_over && last.health > 0 {
+                    last.score() + bonus as isize
+                } else {
+                    last.score()
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Scoring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scoring::Official => write!(f, "official"),
+            Scoring::MonstersSlain => write!(f, "monsters"),
+            Scoring::WeaponEfficiency => write!(f, "weapon"),
+            Scoring::RoomsCleared => write!(f, "rooms"),
+            Scoring::Speed => write!(f, "speed"),
+        }
+    }
+}
+
+impl FromStr for Scoring {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Scoring, ()> {
+        Scoring::iter()
+            .find(|scoring| scoring.to_string() == s)
+            .ok_or(())
+    }
+}
@@ -0,0 +1,272 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    card::Role,
+    game::{Action, Position, State},
+    rules::RuleSet,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub winnable: bool,
+    pub score: isize,
+    pub line: Vec<Action>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Bound {
+    Exact(isize, Action),
+    AtMost(isize),
+}
+
+#[derive(Debug, Default)]
+pub struct Solver {
+    memo: HashMap<Position, Bound>,
+    // The deal and rules the memo was filled for. Positions do not record
+    // them, but the rules and, with shuffled runs, the seed decide what
+    // follows from a position.
+    deal: Option<(u64, Arc<RuleSet>)>,
+    memo_limit: Option<usize>,
+}
+
+// Every remaining monster costs at least its value minus the best weapon that
+// could still be used against it, whether it is fought or left in the dungeon,
+// and health can grow by at most the remaining potions.
+fn upper_bound(state: &State) -> isize {
+    let remaining = || state.deck.iter().chain(state.open.iter().flatten());
+    let best_new_weapon = remaining()
+        .filter(|c| c.role == Role::Weapon)
+        .map(|c| c.rank.value())
+        .max()
+        .unwrap_or(0);
+    let potions = remaining().filter(|c| c.role == Role::Potion);
+    let best_potion = potions.clone().map(|c| c.rank.value()).max().unwrap_or(0);
+
+    let damage: isize = remaining()
+        .filter(|c| c.role == Role::Monster)
+        .map(|&c| {
+            let weapon = match state.weapon {
+                Some(weapon) if state.can_use_weapon(c) => weapon.rank.value(),
+                _ => 0,
+            };
+            (c.rank.value() - weapon.max(best_new_weapon)).max(0)
+        })
+        .sum();
+
+    let health = state.health + potions.map(|c| c.rank.value()).sum::<isize>() - damage;
+    if health >= state.rules.max_health {
+        health.min(state.rules.heal_cap()) + best_potion
+    } else {
+        health
+    }
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver::default()
+    }
+
+    // Bounds memory by forgetting every position once the memo holds `limit`
+    // of them, at the cost of searching some positions again.
+    pub fn with_memo_limit(limit: usize) -> Solver {
+        Solver {
+            memo_limit: Some(limit),
+            ..Solver::default()
+        }
+    }
+
+    fn start(&mut self, state: &State) {
+        let same_deal = self.deal.as_ref().is_some_and(|(seed, rules)| {
+            *seed == state.seed && (Arc::ptr_eq(rules, &state.rules) || **rules == *state.rules)
+        });
+        if !same_deal {
+            self.memo.clear();
+            self.deal = Some((state.seed, state.rules.clone()));
+        }
+    }
+
+    pub fn solve(&mut self, state: &State) -> Solution {
+        self.start(state);
+        let score = self.search(state, isize::MIN);
+
+        let mut line = Vec::new();
+        let mut current = state.clone();
+        while let Some(action) = self.best_action(&current) {
+            current = current
+                .apply(action)
+                .expect("solver only records legal actions");
+            line.push(action);
+        }
+
+        Solution {
+            winnable: score > 0,
+            score,
+            line,
+        }
+    }
+
+    // The bound only looks at the cards left in the deck, so endless dungeons,
+    // which keep dealing new ones, cannot be solved.
+    pub fn best_action(&mut self, state: &State) -> Option<Action> {
+        if state.game_over || state.rules.endless {
+            return None;
+        }
+        self.start(state);
+        self.search(state, isize::MIN);
+        match self.memo[&state.position()] {
+            Bound::Exact(_, action) => Some(action),
+            Bound::AtMost(_) => unreachable!("searching without a bound yields an exact score"),
+        }
+    }
+
+    fn search(&mut self, state: &State, alpha: isize) -> isize {
+        if state.game_over || state.rules.endless {
+            return state.score();
+        }
+
+        let position = state.position();
+        match self.memo.get(&position) {
+            Some(Bound::Exact(score, _)) => return *score,
+            Some(Bound::AtMost(score)) if *score <= alpha => return *score,
+            _ => {}
+        }
+        let bound = upper_bound(state);
+        if bound <= alpha {
+            return bound;
+        }
+
+        let alpha_in = alpha;
+        let mut alpha = alpha;
+        let mut best: Option<(isize, Action)> = None;
+        for action in state.legal_actions() {
+            let next = state
+                .apply(action)
+                .expect("legal actions can always be applied");
+            let score = self.search(&next, alpha);
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, action));
+            }
+            alpha = alpha.max(score);
+            if score >= bound {
+                break;
+            }
+        }
+
+        let (score, action) = best.expect("a running game always has a legal action");
+        let entry = if score > alpha_in {
+            Bound::Exact(score, action)
+        } else {
+            Bound::AtMost(score)
+        };
+        if self
+            .memo_limit
+            .is_some_and(|limit| self.memo.len() >= limit)
+        {
+            self.memo.clear();
+        }
+        self.memo.insert(position, entry);
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{RunPlacement, WeaponRule};
+
+    const DECKS: [&str; 3] = [
+        "2-4 S\n2-3 H\n2 D\n",
+        "2-4 SC\n3 H\n4 D\n",
+        "2-3 SC\n2 H\n2-3 D\n",
+    ];
+
+    fn brute_force(state: &State) -> isize {
+        if state.game_over {
+            return state.score();
+        }
+        state
+            .legal_actions()
+            .into_iter()
+            .map(|action| brute_force(&state.apply(action).unwrap()))
+            .max()
+            .unwrap()
+    }
+
+    fn variants() -> Vec<RuleSet> {
+        let base = RuleSet {
+            max_health: 5,
+            ..RuleSet::official()
+        };
+        vec![
+            base.clone(),
+            RuleSet {
+                room_size: 3,
+                plays_per_room: 2,
+                ..base.clone()
+            },
+            RuleSet {
+                weapon_rule: WeaponRule::Strict,
+                ..base.clone()
+            },
+            RuleSet {
+                heals_per_room: 2,
+                carry_heals: true,
+                overheal: 3,
+                ..base.clone()
+            },
+            RuleSet {
+                max_runs: Some(1),
+                run_cost: 2,
+                ..base.clone()
+            },
+            RuleSet {
+                run_placement: RunPlacement::Shuffle,
+                ..base
+            },
+        ]
+    }
+
+    #[test]
+    fn matches_exhaustive_search() {
+        // One solver for every case, which must not mix up deals or rules.
+        let mut shared = Solver::new();
+        for deck in DECKS {
+            for rules in variants() {
+                let rules = RuleSet {
+                    deck: deck.parse().unwrap(),
+                    ..rules
+                };
+                for seed in 0..5 {
+                    let state = State::with_seed(rules.clone(), seed);
+                    let expected = brute_force(&state);
+                    let solution = Solver::new().solve(&state);
+                    assert_eq!(solution.score, expected, "seed {seed} with {rules}");
+                    assert_eq!(solution.winnable, expected > 0);
+                    assert_eq!(shared.solve(&state).score, expected);
+
+                    let end = solution
+                        .line
+                        .iter()
+                        .fold(state, |state, &action| state.apply(action).unwrap());
+                    assert!(end.game_over);
+                    assert_eq!(end.score(), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn memo_limit_keeps_the_result() {
+        let rules = RuleSet {
+            deck: DECKS[1].parse().unwrap(),
+            ..RuleSet::official()
+        };
+        for seed in 0..5 {
+            let state = State::with_seed(rules.clone(), seed);
+            assert_eq!(
+                Solver::with_memo_limit(16).solve(&state).score,
+                brute_force(&state)
+            );
+        }
+    }
+}
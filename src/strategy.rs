@@ -0,0 +1,116 @@
+use rand::{rngs::StdRng, seq::IndexedRandom, SeedableRng};
+
+use crate::{
+    game::{Action, State},
+    solver::Solver,
+};
+
+pub trait Strategy {
+    fn choose(&mut self, state: &State) -> Option<Action>;
+}
+
+pub struct RandomStrategy {
+    rng: StdRng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> RandomStrategy {
+        RandomStrategy {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn choose(&mut self, state: &State) -> Option<Action> {
+        state.legal_actions().choose(&mut self.rng).copied()
+    }
+}
+
+fn weapon_strength(state: &State) -> isize {
+    match (state.weapon, state.killed_with_weapon.last()) {
+        (None, _) => 0,
+        (Some(weapon), None) => weapon.rank.value(),
+        (Some(weapon), Some(last_killed)) => weapon.rank.value().min(last_killed.rank.value()),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Greedy;
+
+impl Strategy for Greedy {
+    fn choose(&mut self, state: &State) -> Option<Action> {
+        state.legal_actions().into_iter().max_by_key(|&action| {
+            let next = state.apply(action).unwrap();
+            (next.health, weapon_strength(&next), action != Action::Run)
+        })
+    }
+}
+
+// Only looks ahead within the current room, so the bot never peeks at cards
+// that have not been dealt yet.
+#[derive(Debug)]
+pub struct Lookahead {
+    depth: usize,
+}
+
+impl Lookahead {
+    pub fn new(depth: usize) -> Lookahead {
+        Lookahead { depth }
+    }
+
+    fn evaluate(state: &State) -> isize {
+        if state.game_over {
+            if state.health > 0 {
+                1000 + state.score()
+            } else {
+                state.score() - 1000
+            }
+        } else {
+            state.health + weapon_strength(state) - state.deck.len() as isize
+        }
+    }
+
+    fn search(&self, state: &State, depth: usize) -> isize {
+        if depth == 0 || state.game_over {
+            return Lookahead::evaluate(state);
+        }
+
+        state
+            .legal_actions()
+            .into_iter()
+            .map(|action| self.value(state, action, depth))
+            .max()
+            .unwrap()
+    }
+
+    fn value(&self, state: &State, action: Action, depth: usize) -> isize {
+        let next = state.apply(action).unwrap();
+        if action == Action::Run || next.rooms_cleared != state.rooms_cleared {
+            Lookahead::evaluate(&next)
+        } else {
+            self.search(&next, depth - 1)
+        }
+    }
+}
+
+impl Default for Lookahead {
+    fn default() -> Lookahead {
+        Lookahead::new(3)
+    }
+}
+
+impl Strategy for Lookahead {
+    fn choose(&mut self, state: &State) -> Option<Action> {
+        state
+            .legal_actions()
+            .into_iter()
+            .max_by_key(|&action| self.value(state, action, self.depth.max(1)))
+    }
+}
+
+impl Strategy for Solver {
+    fn choose(&mut self, state: &State) -> Option<Action> {
+        self.best_action(state)
+    }
+}